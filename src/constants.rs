@@ -4,4 +4,7 @@ pub(crate) static G_ACC: f64 = 9.81;
 //World origin, is always (0.0, 0.0, 0.0).
 pub(crate) static WORLD_ORIGIN: (f64,f64,f64) = (0.0, 0.0, 0.0);
 /// The maximal difference between two double values until which they are still considered equal.
-pub(crate) static F64_DELTA: f64 = 0.000001;
\ No newline at end of file
+pub(crate) static F64_DELTA: f64 = 0.000001;
+/// The maximal number of parent coordinate systems that are walked before a transformation into world
+/// coordinates is aborted.
+pub(crate) static MAX_COORD_SYSTEM_DEPTH: usize = 1024;
\ No newline at end of file
@@ -0,0 +1,265 @@
+#![allow(dead_code)]
+//! This module contains reading and writing of point clouds in a self-describing binary format.
+//!
+//! A vector file starts with a header of 14 bytes:
+//!
+//! | Bytes | Content                                                      |
+//! |-------|--------------------------------------------------------------|
+//! | 0..4  | The magic `PSVF`                                             |
+//! | 4     | The format version, currently 1                              |
+//! | 5     | The endianness marker: 0 for little endian, 1 for big endian |
+//! | 6..14 | The number of vectors as u64                                 |
+//!
+//! It is followed by the x, y and z values of every vector as f64. All numbers use the byte order
+//! given by the endianness marker.
+
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+
+use crate::physics::vectors::Vector;
+
+/// The magic bytes every vector file starts with.
+pub const VECTOR_FILE_MAGIC: [u8; 4] = *b"PSVF";
+/// The version of the vector file format written by [write_vector_file].
+pub const VECTOR_FILE_VERSION: u8 = 1;
+const LITTLE_ENDIAN: u8 = 0;
+const BIG_ENDIAN: u8 = 1;
+
+/// Writes the vectors as a vector file in little endian byte order.
+/// # Examples
+/// ```rust
+/// let mut buffer = Vec::new();
+/// write_vector_file(&[Vector::new(1.0, 2.0, 3.0)], &mut buffer).unwrap();
+/// assert_eq!(14 + 24, buffer.len());
+/// ```
+pub fn write_vector_file(points: &[Vector], w: &mut impl Write) -> io::Result<()> {
+    w.write_all(&VECTOR_FILE_MAGIC)?;
+    w.write_all(&[VECTOR_FILE_VERSION, LITTLE_ENDIAN])?;
+    w.write_all(&(points.len() as u64).to_le_bytes())?;
+    for point in points {
+        w.write_all(&point.get_x().to_le_bytes())?;
+        w.write_all(&point.get_y().to_le_bytes())?;
+        w.write_all(&point.get_z().to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Reads all vectors of a vector file written by [write_vector_file].
+///
+/// Fails with [io::ErrorKind::InvalidData] if the magic, the version or the endianness marker do
+/// not match, and with [io::ErrorKind::UnexpectedEof] if the file holds fewer vectors than its
+/// header announces.
+/// # Examples
+/// ```rust
+/// let mut buffer = Vec::new();
+/// write_vector_file(&[Vector::new(1.0, 2.0, 3.0)], &mut buffer).unwrap();
+/// let points = read_vector_file(&mut buffer.as_slice()).unwrap();
+/// assert_eq!(vec![Vector::new(1.0, 2.0, 3.0)], points);
+/// ```
+pub fn read_vector_file(r: &mut impl Read) -> io::Result<Vec<Vector>> {
+    VectorFileReader::new(r)?.collect()
+}
+
+/// Reads the vectors of a vector file one at a time, so that the memory use does not depend on
+/// the size of the file.
+///
+/// The header is read and validated on creation. Afterwards every call to next reads one vector,
+/// until as many vectors as announced in the header are read. If reading a vector fails, e.g.
+/// because the file ends in the middle of a record, the error is returned once and the iteration
+/// ends.
+/// # Examples
+/// ```rust
+/// let file = std::io::BufReader::new(std::fs::File::open("points.psvf")?);
+/// for point in VectorFileReader::new(file)? {
+///     println!("{}", point?);
+/// }
+/// ```
+pub struct VectorFileReader<R: Read> {
+    reader: R,
+    big_endian: bool,
+    remaining: u64
+}
+
+impl<R: Read> VectorFileReader<R> {
+    /// Reads the header from the reader and returns a reader for the following vectors. Fails in
+    /// the same cases as [read_vector_file] does for the header.
+    pub fn new(mut reader: R) -> io::Result<VectorFileReader<R>> {
+        let header = read_header(&mut reader)?;
+        Ok(VectorFileReader { reader, big_endian: header.big_endian, remaining: header.count })
+    }
+    /// Returns the number of vectors that are still to be read.
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+}
+
+impl<R: Read> Iterator for VectorFileReader<R> {
+    type Item = io::Result<Vector>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let vector = read_vector(&mut self.reader, self.big_endian);
+        self.remaining = if vector.is_ok() { self.remaining - 1 } else { 0 };
+        Some(vector)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // A broken file may end early, so only the upper bound is known.
+        (0, usize::try_from(self.remaining).ok())
+    }
+}
+
+/// The validated header of a vector file.
+struct Header {
+    big_endian: bool,
+    count: u64
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_header(r: &mut impl Read) -> io::Result<Header> {
+    let mut bytes = [0; 14];
+    r.read_exact(&mut bytes)?;
+    if bytes[0..4] != VECTOR_FILE_MAGIC {
+        return Err(invalid_data(format!("not a vector file: expected magic {:?}, found {:?}",
+                                        VECTOR_FILE_MAGIC, &bytes[0..4])));
+    }
+    if bytes[4] != VECTOR_FILE_VERSION {
+        return Err(invalid_data(format!("unsupported vector file version {}, expected {}",
+                                        bytes[4], VECTOR_FILE_VERSION)));
+    }
+    let big_endian = match bytes[5] {
+        LITTLE_ENDIAN => false,
+        BIG_ENDIAN => true,
+        marker => return Err(invalid_data(format!("invalid endianness marker {}", marker))),
+    };
+    let mut count = [0; 8];
+    count.copy_from_slice(&bytes[6..14]);
+    let count = if big_endian { u64::from_be_bytes(count) } else { u64::from_le_bytes(count) };
+    Ok(Header { big_endian, count })
+}
+
+fn read_vector(r: &mut impl Read, big_endian: bool) -> io::Result<Vector> {
+    let mut bytes = [0; 24];
+    r.read_exact(&mut bytes)?;
+    let mut values = [0.0; 3];
+    for (value, chunk) in values.iter_mut().zip(bytes.chunks_exact(8)) {
+        let mut component = [0; 8];
+        component.copy_from_slice(chunk);
+        *value = if big_endian { f64::from_be_bytes(component) } else { f64::from_le_bytes(component) };
+    }
+    Ok(Vector::new(values[0], values[1], values[2]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip(){
+        let points = vec![
+            Vector::new(1.0, -2.5, 3.25),
+            Vector::new(0.0, 0.0, 0.0),
+            Vector::new(f64::MAX, f64::MIN_POSITIVE, -1e-300),
+        ];
+        let mut buffer = Vec::new();
+        write_vector_file(&points, &mut buffer).unwrap();
+        assert_eq!(14 + 3 * 24, buffer.len());
+        assert_eq!(&VECTOR_FILE_MAGIC, &buffer[0..4]);
+
+        assert_eq!(points, read_vector_file(&mut buffer.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn round_trip_empty(){
+        let mut buffer = Vec::new();
+        write_vector_file(&[], &mut buffer).unwrap();
+        assert!(read_vector_file(&mut buffer.as_slice()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn big_endian(){
+        let mut buffer = b"PSVF".to_vec();
+        buffer.extend_from_slice(&[VECTOR_FILE_VERSION, BIG_ENDIAN]);
+        buffer.extend_from_slice(&1u64.to_be_bytes());
+        for value in [4.0_f64, 5.0, 6.0].iter() {
+            buffer.extend_from_slice(&value.to_be_bytes());
+        }
+        assert_eq!(vec![Vector::new(4.0, 5.0, 6.0)], read_vector_file(&mut buffer.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn corrupted_magic(){
+        let mut buffer = Vec::new();
+        write_vector_file(&[Vector::new(1.0, 2.0, 3.0)], &mut buffer).unwrap();
+        buffer[0] = b'X';
+
+        let error = read_vector_file(&mut buffer.as_slice()).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+        assert!(error.to_string().contains("not a vector file"));
+    }
+
+    #[test]
+    fn wrong_version(){
+        let mut buffer = Vec::new();
+        write_vector_file(&[], &mut buffer).unwrap();
+        buffer[4] = VECTOR_FILE_VERSION + 1;
+
+        let error = read_vector_file(&mut buffer.as_slice()).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+        assert!(error.to_string().contains("version"));
+    }
+
+    #[test]
+    fn missing_vectors(){
+        let mut buffer = Vec::new();
+        write_vector_file(&[Vector::new(1.0, 2.0, 3.0)], &mut buffer).unwrap();
+        buffer.truncate(buffer.len() - 1);
+
+        let error = read_vector_file(&mut buffer.as_slice()).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, error.kind());
+    }
+
+    #[test]
+    fn streaming_reader(){
+        let points: Vec<Vector> = (0..100).map(|i| Vector::new(i as f64, -(i as f64), 0.5 * i as f64)).collect();
+        let mut buffer = Vec::new();
+        write_vector_file(&points, &mut buffer).unwrap();
+
+        let mut reader = VectorFileReader::new(buffer.as_slice()).unwrap();
+        assert_eq!(100, reader.remaining());
+        assert_eq!(Vector::new(0.0, 0.0, 0.0), reader.next().unwrap().unwrap());
+        assert_eq!(Vector::new(1.0, -1.0, 0.5), reader.next().unwrap().unwrap());
+        assert_eq!(98, reader.remaining());
+
+        let rest: Vec<Vector> = reader.map(|point| point.unwrap()).collect();
+        assert_eq!(98, rest.len());
+        assert_eq!(&points[2..], rest.as_slice());
+    }
+
+    #[test]
+    fn streaming_reader_truncated(){
+        let points = [Vector::new(1.0, 2.0, 3.0), Vector::new(4.0, 5.0, 6.0), Vector::new(7.0, 8.0, 9.0)];
+        let mut buffer = Vec::new();
+        write_vector_file(&points, &mut buffer).unwrap();
+        buffer.truncate(buffer.len() - 10);
+
+        let mut reader = VectorFileReader::new(buffer.as_slice()).unwrap();
+        assert_eq!(points[0], reader.next().unwrap().unwrap());
+        assert_eq!(points[1], reader.next().unwrap().unwrap());
+        assert_eq!(io::ErrorKind::UnexpectedEof, reader.next().unwrap().unwrap_err().kind());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn streaming_reader_bad_header(){
+        let error = VectorFileReader::new(&b"PSV"[..]).err().unwrap();
+        assert_eq!(io::ErrorKind::UnexpectedEof, error.kind());
+        let error = VectorFileReader::new(&b"ABCD\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00"[..]).err().unwrap();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+    }
+}
@@ -39,7 +39,9 @@
  */
 
 mod constants;
+mod io;
 mod physics;
+mod signal;
 mod utils;
 
 
@@ -0,0 +1,660 @@
+//! This module contains acoustic helper functions used for sonar calculations.
+
+use crate::constants::F64_DELTA;
+use crate::physics::hydrostatics::SEAWATER_DENSITY;
+use crate::physics::vectors::Vector;
+
+/// Returns the length in m of a target that spans the angular extent (in rad) at the given range
+/// (in m), calculated as `2 * range * tan(angular_extent / 2)`.
+/// # Examples
+/// ```rust
+/// let length = target_length_m(0.1, 1000.0);
+/// assert!((length - 100.0834).abs() < 0.0001);
+/// ```
+pub fn target_length_m(angular_extent_rad: f64, range_m: f64) -> f64 {
+    2.0 * range_m * (angular_extent_rad / 2.0).tan()
+}
+
+/// Returns the angular extent in rad that a target of the given length (in m) spans at the given
+/// range (in m). This is the inverse of [target_length_m].
+/// # Examples
+/// ```rust
+/// let extent = angular_extent_for_length(100.0, 1000.0);
+/// assert!(equal_within_delta(100.0, target_length_m(extent, 1000.0)));
+/// ```
+pub fn angular_extent_for_length(length_m: f64, range_m: f64) -> f64 {
+    2.0 * (length_m / (2.0 * range_m)).atan()
+}
+
+/// Returns the beamwidth in rad of a circular aperture (e.g. a dish or a circular transducer)
+/// with the given diameter (in m) at the given wavelength (in m). The beamwidth is measured
+/// to the first null and approximated as `1.22 * wavelength / diameter`.
+/// # Examples
+/// ```rust
+/// let beamwidth = circular_aperture_beamwidth_rad(0.5, 0.01);
+/// assert!(equal_within_delta(0.0244, beamwidth));
+/// ```
+pub fn circular_aperture_beamwidth_rad(diameter_m: f64, wavelength_m: f64) -> f64 {
+    1.22 * wavelength_m / diameter_m
+}
+
+/// Returns true if the echoes of two pings overlap in time at the receiver.
+///
+/// A ping sent at time t (in s) returns from a target at the given range (in m) after the two-way
+/// travel time `2 * range / sound_speed` and lasts for the pulse duration (in s). Two pings
+/// interfere if their echo windows overlap.
+/// # Examples
+/// ```rust
+/// assert!(pings_interfere(0.0, 750.0, 0.5, 375.0, 0.1, 1500.0));
+/// assert!(!pings_interfere(0.0, 750.0, 2.0, 750.0, 0.1, 1500.0));
+/// ```
+pub fn pings_interfere(t1: f64, range1: f64, t2: f64, range2: f64, pulse_duration_s: f64,
+                       sound_speed_mps: f64) -> bool {
+    let echo_start_1 = t1 + 2.0 * range1 / sound_speed_mps;
+    let echo_start_2 = t2 + 2.0 * range2 / sound_speed_mps;
+    (echo_start_1 - echo_start_2).abs() < pulse_duration_s
+}
+
+/// Returns the expected number of false alarms for the given false alarm rate (per cell and
+/// second), number of resolution cells and scan time (in s), calculated as
+/// `rate * cells * scan_time`.
+/// # Examples
+/// ```rust
+/// assert!(equal_within_delta(5.0, expected_false_alarms(0.001, 500, 10.0)));
+/// ```
+pub fn expected_false_alarms(false_alarm_rate: f64, num_cells: usize, scan_time_s: f64) -> f64 {
+    false_alarm_rate * num_cells as f64 * scan_time_s
+}
+
+/// Returns the gain in dB from integrating num_pulses pulses.
+///
+/// Coherent integration adds the pulses in phase and gains `10 * log10(n)`. Incoherent
+/// integration only adds the envelopes and is approximated as `5 * log10(n)`.
+/// # Examples
+/// ```rust
+/// assert!(equal_within_delta(10.0, integration_gain_db(10, true)));
+/// assert!(equal_within_delta(5.0, integration_gain_db(10, false)));
+/// ```
+pub fn integration_gain_db(num_pulses: usize, coherent: bool) -> f64 {
+    let factor = if coherent { 10.0 } else { 5.0 };
+    factor * (num_pulses as f64).log10()
+}
+
+/// Returns the frequency in Hz of an active sonar echo that was emitted at emitted_hz and
+/// reflected by a target, calculated as `f * (c + v) / (c - v)`.
+///
+/// The closing speed v (in m/s) is the rate at which the distance between the sonar and the
+/// target shrinks: it is positive for an approaching target and negative for a receding one.
+/// # Examples
+/// ```rust
+/// assert_eq!(10000.0, doppler_shift(10000.0, 0.0, 1500.0));
+/// assert!(doppler_shift(10000.0, 5.0, 1500.0) > 10000.0);
+/// ```
+pub fn doppler_shift(emitted_hz: f64, closing_speed_mps: f64, sound_speed_mps: f64) -> f64 {
+    emitted_hz * (sound_speed_mps + closing_speed_mps) / (sound_speed_mps - closing_speed_mps)
+}
+
+/// Returns the frequency in Hz received from a source that emits at emitted_hz while closing on a
+/// stationary receiver, calculated as `f * c / (c - v)`. This is the one-way shift heard by a
+/// passive sonar. The closing speed follows the convention of [doppler_shift]: positive for an
+/// approaching source.
+/// # Examples
+/// ```rust
+/// assert_eq!(10000.0, doppler_shift_one_way(10000.0, 0.0, 1500.0));
+/// ```
+pub fn doppler_shift_one_way(emitted_hz: f64, closing_speed_mps: f64, sound_speed_mps: f64) -> f64 {
+    emitted_hz * sound_speed_mps / (sound_speed_mps - closing_speed_mps)
+}
+
+/// Returns the speed of sound in seawater in m/s after the nine-term equation of Mackenzie (1981)
+/// for the temperature in °C, the salinity in parts per thousand and the depth in m.
+///
+/// The equation is valid for temperatures from 2 to 30 °C, salinities from 25 to 40 ppt and
+/// depths from 0 to 8000 m, with a standard error of about 0.07 m/s. Outside these ranges the
+/// result is an extrapolation.
+/// # Examples
+/// ```rust
+/// let c = sound_speed_seawater(25.0, 35.0, 1000.0);
+/// assert!((c - 1550.744).abs() < 0.001);
+/// ```
+pub fn sound_speed_seawater(temp_c: f64, salinity_ppt: f64, depth_m: f64) -> f64 {
+    let t = temp_c;
+    let s = salinity_ppt - 35.0;
+    let d = depth_m;
+    1448.96 + 4.591 * t - 5.304e-2 * t.powi(2) + 2.374e-4 * t.powi(3)
+        + 1.340 * s + 1.630e-2 * d + 1.675e-7 * d.powi(2)
+        - 1.025e-2 * t * s - 7.139e-13 * t * d.powi(3)
+}
+
+/// Returns the absorption of sound in seawater in dB/km after the model of Francois and
+/// Garrison (1982) for the frequency in kHz, the temperature in °C, the salinity in parts per
+/// thousand and the depth in m.
+///
+/// The model adds the relaxation absorption of boric acid, which dominates below a few kHz, the
+/// relaxation absorption of magnesium sulfate, which dominates up to a few hundred kHz, and the
+/// viscous absorption of pure water above. A pH of 8 is assumed. The model is valid for
+/// frequencies from 0.2 to 1000 kHz, temperatures from -2 to 22 °C, salinities from 30 to
+/// 35 ppt and depths from 0 to 3500 m.
+/// # Examples
+/// ```rust
+/// let absorption = absorption_coefficient_db_per_km(10.0, 4.0, 35.0, 0.0);
+/// assert!((absorption - 1.15).abs() < 0.01);
+/// ```
+pub fn absorption_coefficient_db_per_km(freq_khz: f64, temp_c: f64, salinity_ppt: f64, depth_m: f64) -> f64 {
+    let ph = 8.0;
+    let f2 = freq_khz * freq_khz;
+    let t = temp_c;
+    let theta = t + 273.0;
+    let c = 1412.0 + 3.21 * t + 1.19 * salinity_ppt + 0.0167 * depth_m;
+
+    // Boric acid
+    let a1 = 8.86 / c * 10.0_f64.powf(0.78 * ph - 5.0);
+    let f1 = 2.8 * (salinity_ppt / 35.0).sqrt() * 10.0_f64.powf(4.0 - 1245.0 / theta);
+    let boric_acid = a1 * f1 * f2 / (f2 + f1 * f1);
+
+    // Magnesium sulfate
+    let a2 = 21.44 * salinity_ppt / c * (1.0 + 0.025 * t);
+    let p2 = 1.0 - 1.37e-4 * depth_m + 6.2e-9 * depth_m * depth_m;
+    let fm = 8.17 * 10.0_f64.powf(8.0 - 1990.0 / theta) / (1.0 + 0.0018 * (salinity_ppt - 35.0));
+    let magnesium_sulfate = a2 * p2 * fm * f2 / (f2 + fm * fm);
+
+    // Pure water
+    let a3 = if t <= 20.0 {
+        4.937e-4 - 2.59e-5 * t + 9.11e-7 * t.powi(2) - 1.50e-8 * t.powi(3)
+    } else {
+        3.964e-4 - 1.146e-5 * t + 1.45e-7 * t.powi(2) - 6.5e-10 * t.powi(3)
+    };
+    let p3 = 1.0 - 3.83e-5 * depth_m + 4.9e-10 * depth_m * depth_m;
+    let pure_water = a3 * p3 * f2;
+
+    boric_acid + magnesium_sulfate + pure_water
+}
+
+/// Returns the signal excess in dB of the passive sonar equation,
+/// `SL - TL - (NL - DI) - DT`, for the source level of the target, the transmission loss along the
+/// path, the noise level at the receiver, the directivity index of the receiving array and the
+/// detection threshold, all in dB. A positive signal excess means the target is detectable.
+/// # Examples
+/// ```rust
+/// let excess = passive_signal_excess(140.0, 60.0, 70.0, 15.0, 10.0);
+/// assert!(equal_within_delta(15.0, excess));
+/// ```
+pub fn passive_signal_excess(source_level: f64, transmission_loss: f64, noise_level: f64,
+                             directivity_index: f64, detection_threshold: f64) -> f64 {
+    source_level - transmission_loss - (noise_level - directivity_index) - detection_threshold
+}
+
+/// Returns the range in m at which spherical spreading with `20 * log10(r) + spherical_offset_db`
+/// and cylindrical spreading with `10 * log10(r) + cylindrical_offset_db` cause the same
+/// transmission loss, with r in m.
+///
+/// Setting both losses equal gives `10 * log10(r) = cylindrical_offset_db - spherical_offset_db`,
+/// so the range is `10^((cylindrical_offset_db - spherical_offset_db) / 10)`. Below this range
+/// the spherical model predicts the smaller loss, beyond it the cylindrical one.
+/// # Examples
+/// ```rust
+/// let range = spreading_transition_range_m(0.0, 30.0);
+/// assert!(equal_within_delta(1000.0, range));
+/// ```
+pub fn spreading_transition_range_m(spherical_offset_db: f64, cylindrical_offset_db: f64) -> f64 {
+    10.0_f64.powf((cylindrical_offset_db - spherical_offset_db) / 10.0)
+}
+
+/// The salinity in parts per thousand assumed by [min_detectable_ts_db].
+const TYPICAL_SALINITY_PPT: f64 = 35.0;
+
+/// Returns the temperature in °C at which [sound_speed_seawater] yields sound_speed_mps for the
+/// given salinity and depth, found by bisection over -10 to 40 °C where the speed rises with the
+/// temperature. Speeds outside this span return its nearest end.
+fn temperature_from_sound_speed(sound_speed_mps: f64, salinity_ppt: f64, depth_m: f64) -> f64 {
+    let mut low = -10.0;
+    let mut high = 40.0;
+    while high - low > F64_DELTA {
+        let middle = 0.5 * (low + high);
+        if sound_speed_seawater(middle, salinity_ppt, depth_m) < sound_speed_mps {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    0.5 * (low + high)
+}
+
+/// Returns the smallest target strength in dB that an active sonar detects at the given range
+/// in m, solving the noise-limited active sonar equation `SL - 2TL + TS - NL - DT = 0` for TS.
+///
+/// The one-way transmission loss TL combines spherical spreading and absorption as
+/// `20 * log10(range) + α * range / 1000`. The absorption α in dB/km follows
+/// [absorption_coefficient_db_per_km] for freq_khz in near-surface water (0 m depth) with a
+/// salinity of 35 ppt. The sound speed stands for the water temperature: the temperature is
+/// taken as the one at which [sound_speed_seawater] yields sound_speed_mps under these
+/// conditions, e.g. about 13 °C for 1500 m/s.
+/// # Examples
+/// ```rust
+/// // At 10 kHz and 1500 m/s the absorption is about 0.89 dB/km.
+/// let ts = min_detectable_ts_db(1000.0, 220.0, 70.0, 10.0, 10.0, 1500.0);
+/// assert!((ts - -18.23).abs() < 0.01);
+/// ```
+pub fn min_detectable_ts_db(range_m: f64, source_level: f64, noise_level: f64, detection_threshold: f64,
+                            freq_khz: f64, sound_speed_mps: f64) -> f64 {
+    let temp_c = temperature_from_sound_speed(sound_speed_mps, TYPICAL_SALINITY_PPT, 0.0);
+    let absorption = absorption_coefficient_db_per_km(freq_khz, temp_c, TYPICAL_SALINITY_PPT, 0.0);
+    let transmission_loss = 20.0 * range_m.log10() + absorption * range_m / 1000.0;
+    detection_threshold + noise_level + 2.0 * transmission_loss - source_level
+}
+
+/// Returns the unit direction of a sound ray after it crosses the boundary between two layers
+/// with the sound speeds c1 (incident side) and c2, or None if the ray is totally reflected.
+///
+/// Snell's law `sin(θ2) / sin(θ1) = c2 / c1` is applied in vector form, with the angles measured
+/// against the normal of the boundary. The normal may point to either side of the boundary and
+/// neither direction needs to be normalized. A ray crossing into faster water bends away from
+/// the normal and is totally reflected beyond the critical angle `asin(c1 / c2)`. None is
+/// returned as well if a direction has no length.
+/// # Examples
+/// ```rust
+/// let down = Vector::new(0.0, 0.0, -1.0);
+/// assert_eq!(Some(down), refract(&down, &Vector::new(0.0, 0.0, 1.0), 1500.0, 1520.0));
+/// ```
+pub fn refract(incident_dir: &Vector, normal: &Vector, c1: f64, c2: f64) -> Option<Vector> {
+    let dir = incident_dir.normalize()?;
+    let mut normal = normal.normalize()?;
+    let mut cos_incident = -normal.dot(&dir);
+    if cos_incident < 0.0 {
+        normal = normal.scale(-1.0);
+        cos_incident = -cos_incident;
+    }
+    let ratio = c2 / c1;
+    let sin2_refracted = ratio * ratio * (1.0 - cos_incident * cos_incident);
+    if sin2_refracted > 1.0 {
+        return None;
+    }
+    let cos_refracted = (1.0 - sin2_refracted).sqrt();
+    dir.scale(ratio).add(&normal.scale(ratio * cos_incident - cos_refracted)).normalize()
+}
+
+/// The nominal sound speed in seawater in m/s, used where the sound speed of the water column is
+/// not known more precisely.
+pub const NOMINAL_SOUND_SPEED: f64 = 1500.0;
+
+/// Returns the loss in dB of a plane wave reflected by a flat seabed at the grazing angle in rad
+/// (see [grazing_angle](crate::physics::geometry::grazing_angle)), calculated from the Rayleigh
+/// reflection coefficient of the interface between water and a fluid sediment with the given
+/// sound speed in m/s and density in kg/m³. The water has [NOMINAL_SOUND_SPEED] and
+/// [SEAWATER_DENSITY].
+///
+/// Below the critical angle `acos(c_water / c_sediment)` of a sediment faster than water the
+/// wave is totally reflected and the loss is 0 dB. Steeper waves partly enter the sediment. At
+/// the angle of intromission, where no wave is reflected at all, the loss is infinite.
+/// # Examples
+/// ```rust
+/// // Sand at normal incidence reflects about a third of the amplitude.
+/// let loss = bottom_reflection_loss_db(FRAC_PI_2, 1650.0, 1900.0);
+/// assert!((loss - 9.32).abs() < 0.01);
+/// ```
+pub fn bottom_reflection_loss_db(grazing_rad: f64, sediment_sound_speed: f64, sediment_density: f64) -> f64 {
+    let density_ratio = sediment_density / SEAWATER_DENSITY;
+    let index = NOMINAL_SOUND_SPEED / sediment_sound_speed;
+    let cos_grazing = grazing_rad.cos();
+    let radicand = index * index - cos_grazing * cos_grazing;
+    if radicand <= 0.0 {
+        return 0.0;
+    }
+    let impedance_term = density_ratio * grazing_rad.sin();
+    let coefficient = (impedance_term - radicand.sqrt()) / (impedance_term + radicand.sqrt());
+    -20.0 * coefficient.abs().log10()
+}
+
+/// Returns the largest range in m at which an active sonar detects a target, i.e. the range at
+/// which the signal excess of the noise-limited active sonar equation
+/// `SL - 2TL + TS - (NL - DI) - DT` falls to 0. All levels are in dB.
+///
+/// The one-way transmission loss combines spherical spreading and absorption as
+/// `TL = 20 * log10(range) + α * range / 1000` with the absorption α in dB/km. The signal excess
+/// shrinks with range, so the range is found by doubling an upper bound and then bisecting down
+/// to [F64_DELTA](crate::constants::F64_DELTA) relative to the range, but at least
+/// [F64_DELTA](crate::constants::F64_DELTA) m. A target that is not detectable at the reference
+/// range of 1 m returns 0.
+/// # Examples
+/// ```rust
+/// // Without absorption 80 dB of excess at 1 m allow 40 log r = 80, so r = 100 m.
+/// let range = active_detection_range(200.0, 10.0, 90.0, 10.0, 50.0, 0.0);
+/// assert!((range - 100.0).abs() < 0.0001);
+/// ```
+pub fn active_detection_range(source_level: f64, target_strength: f64, noise_level: f64,
+                              directivity_index: f64, detection_threshold: f64,
+                              absorption_db_per_km: f64) -> f64 {
+    let signal_excess = |range_m: f64| {
+        let transmission_loss = 20.0 * range_m.log10() + absorption_db_per_km * range_m / 1000.0;
+        source_level - 2.0 * transmission_loss + target_strength - (noise_level - directivity_index)
+            - detection_threshold
+    };
+    let mut low = 1.0;
+    if signal_excess(low) < 0.0 {
+        return 0.0;
+    }
+    let mut high = 2.0;
+    while signal_excess(high) >= 0.0 && high.is_finite() {
+        low = high;
+        high *= 2.0;
+    }
+    while high - low > F64_DELTA * high.max(1.0) && high.is_finite() {
+        let middle = 0.5 * (low + high);
+        if signal_excess(middle) >= 0.0 {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    low
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::helper_functions::equal_within_delta;
+
+    #[test]
+    fn target_length(){
+        let length = target_length_m(0.1, 1000.0);
+        assert!(equal_within_delta(2000.0 * 0.05_f64.tan(), length));
+        assert_eq!(0.0, target_length_m(0.0, 1000.0));
+    }
+
+    #[test]
+    fn angular_extent_shrinks_with_range(){
+        let near = angular_extent_for_length(50.0, 500.0);
+        let far = angular_extent_for_length(50.0, 2000.0);
+        assert!(far < near);
+    }
+
+    #[test]
+    fn angular_extent_round_trip(){
+        let extent = angular_extent_for_length(75.0, 1200.0);
+        assert!(equal_within_delta(75.0, target_length_m(extent, 1200.0)));
+
+        let length = target_length_m(0.02, 3000.0);
+        assert!(equal_within_delta(0.02, angular_extent_for_length(length, 3000.0)));
+    }
+
+    #[test]
+    fn circular_aperture_beamwidth(){
+        assert!(equal_within_delta(0.122, circular_aperture_beamwidth_rad(1.0, 0.1)));
+        assert!(equal_within_delta(0.0244, circular_aperture_beamwidth_rad(0.5, 0.01)));
+
+        let small = circular_aperture_beamwidth_rad(0.2, 0.05);
+        let large = circular_aperture_beamwidth_rad(0.8, 0.05);
+        assert!(large < small);
+    }
+
+    #[test]
+    fn pings_overlapping(){
+        // Both echoes arrive after 1.0 s.
+        assert!(pings_interfere(0.0, 750.0, 0.5, 375.0, 0.1, 1500.0));
+        // The second echo starts while the first one is still arriving.
+        assert!(pings_interfere(0.0, 750.0, 0.05, 750.0, 0.1, 1500.0));
+    }
+
+    #[test]
+    fn pings_separated(){
+        assert!(!pings_interfere(0.0, 750.0, 2.0, 750.0, 0.1, 1500.0));
+        assert!(!pings_interfere(0.0, 750.0, 0.0, 1500.0, 0.1, 1500.0));
+    }
+
+    #[test]
+    fn expected_false_alarms_scaling(){
+        let base = expected_false_alarms(0.001, 500, 10.0);
+        assert!(equal_within_delta(5.0, base));
+        assert!(equal_within_delta(2.0 * base, expected_false_alarms(0.002, 500, 10.0)));
+        assert!(equal_within_delta(2.0 * base, expected_false_alarms(0.001, 1000, 10.0)));
+        assert!(equal_within_delta(2.0 * base, expected_false_alarms(0.001, 500, 20.0)));
+    }
+
+    #[test]
+    fn integration_gain(){
+        assert!(equal_within_delta(20.0, integration_gain_db(100, true)));
+        assert!(equal_within_delta(10.0, integration_gain_db(100, false)));
+        assert_eq!(0.0, integration_gain_db(1, true));
+
+        for num_pulses in [2, 8, 64] {
+            assert!(integration_gain_db(num_pulses, true) > integration_gain_db(num_pulses, false));
+        }
+    }
+
+    #[test]
+    fn doppler_stationary(){
+        assert_eq!(12000.0, doppler_shift(12000.0, 0.0, 1500.0));
+        assert_eq!(12000.0, doppler_shift_one_way(12000.0, 0.0, 1500.0));
+    }
+
+    #[test]
+    fn doppler_approaching(){
+        let shifted = doppler_shift(10000.0, 10.0, 1500.0);
+        assert!(equal_within_delta(10000.0 * 1510.0 / 1490.0, shifted));
+        assert!(shifted > 10000.0);
+
+        let one_way = doppler_shift_one_way(10000.0, 10.0, 1500.0);
+        assert!(one_way > 10000.0);
+        assert!(one_way < shifted);
+    }
+
+    #[test]
+    fn doppler_receding(){
+        let shifted = doppler_shift(10000.0, -10.0, 1500.0);
+        assert!(equal_within_delta(10000.0 * 1490.0 / 1510.0, shifted));
+        assert!(shifted < 10000.0);
+        assert!(doppler_shift_one_way(10000.0, -10.0, 1500.0) < 10000.0);
+    }
+
+    #[test]
+    fn sound_speed_reference_values(){
+        // Check value given by Mackenzie (1981).
+        assert!((sound_speed_seawater(25.0, 35.0, 1000.0) - 1550.744).abs() < 0.001);
+        assert!((sound_speed_seawater(0.0, 35.0, 0.0) - 1448.96).abs() < 0.001);
+        assert!((sound_speed_seawater(10.0, 35.0, 0.0) - 1489.8).abs() < 0.01);
+    }
+
+    #[test]
+    fn sound_speed_trends(){
+        let base = sound_speed_seawater(10.0, 35.0, 100.0);
+        assert!(sound_speed_seawater(15.0, 35.0, 100.0) > base);
+        assert!(sound_speed_seawater(10.0, 38.0, 100.0) > base);
+        assert!(sound_speed_seawater(10.0, 35.0, 2000.0) > base);
+    }
+
+    #[test]
+    fn absorption_reference_values(){
+        // Values of the Francois-Garrison model at 4 °C, 35 ppt, surface.
+        assert!((absorption_coefficient_db_per_km(1.0, 4.0, 35.0, 0.0) - 0.063).abs() < 0.001);
+        assert!((absorption_coefficient_db_per_km(10.0, 4.0, 35.0, 0.0) - 1.15).abs() < 0.01);
+        assert!((absorption_coefficient_db_per_km(100.0, 4.0, 35.0, 0.0) - 27.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn absorption_rises_with_frequency(){
+        let mut last = 0.0;
+        for freq_khz in [0.5, 1.0, 5.0, 10.0, 50.0, 100.0, 500.0] {
+            let absorption = absorption_coefficient_db_per_km(freq_khz, 10.0, 35.0, 500.0);
+            assert!(absorption > last);
+            last = absorption;
+        }
+        assert!(absorption_coefficient_db_per_km(1.0, 10.0, 35.0, 0.0) < 0.1);
+        assert!(absorption_coefficient_db_per_km(100.0, 10.0, 35.0, 0.0) > 10.0);
+    }
+
+    #[test]
+    fn absorption_drops_with_depth(){
+        assert!(absorption_coefficient_db_per_km(50.0, 4.0, 35.0, 3000.0)
+            < absorption_coefficient_db_per_km(50.0, 4.0, 35.0, 0.0));
+    }
+
+    #[test]
+    fn passive_signal_excess_detectable(){
+        // A loud surface ship at short range.
+        let excess = passive_signal_excess(170.0, 60.0, 70.0, 20.0, 10.0);
+        assert!(equal_within_delta(50.0, excess));
+        assert!(excess > 0.0);
+    }
+
+    #[test]
+    fn passive_signal_excess_undetectable(){
+        // A quiet submarine far away in a noisy sea.
+        let excess = passive_signal_excess(110.0, 90.0, 80.0, 10.0, 12.0);
+        assert!(equal_within_delta(-62.0, excess));
+        assert!(excess < 0.0);
+
+        // The directivity index lowers the effective noise level one to one.
+        assert!(equal_within_delta(excess + 5.0, passive_signal_excess(110.0, 90.0, 80.0, 15.0, 12.0)));
+    }
+
+    #[test]
+    fn spreading_transition_range(){
+        // 20 log r + 5 = 10 log r + 25 gives 10 log r = 20 and r = 100 m.
+        let range = spreading_transition_range_m(5.0, 25.0);
+        assert!(equal_within_delta(100.0, range));
+        let spherical = 20.0 * range.log10() + 5.0;
+        let cylindrical = 10.0 * range.log10() + 25.0;
+        assert!(equal_within_delta(spherical, cylindrical));
+
+        // Equal offsets cross at the reference range of 1 m.
+        assert!(equal_within_delta(1.0, spreading_transition_range_m(12.0, 12.0)));
+    }
+
+    #[test]
+    fn min_detectable_ts_rises_with_range(){
+        let mut last = f64::NEG_INFINITY;
+        for range_m in [100.0, 500.0, 1000.0, 5000.0, 20000.0].iter() {
+            let ts = min_detectable_ts_db(*range_m, 210.0, 65.0, 12.0, 3.5, 1500.0);
+            assert!(ts > last);
+            last = ts;
+        }
+        // Near 0 Hz the absorption is negligible and a tenfold range costs 40 dB of two-way spreading.
+        let near = min_detectable_ts_db(100.0, 210.0, 65.0, 12.0, 0.0, 1500.0);
+        let far = min_detectable_ts_db(1000.0, 210.0, 65.0, 12.0, 0.0, 1500.0);
+        assert!((far - near - 40.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn min_detectable_ts_meets_threshold(){
+        let ts = min_detectable_ts_db(2000.0, 215.0, 70.0, 10.0, 10.0, 1500.0);
+        let temp_c = temperature_from_sound_speed(1500.0, 35.0, 0.0);
+        let absorption = absorption_coefficient_db_per_km(10.0, temp_c, 35.0, 0.0);
+        let transmission_loss = 20.0 * 2000.0_f64.log10() + 2.0 * absorption;
+        let signal_excess = 215.0 - 2.0 * transmission_loss + ts - 70.0 - 10.0;
+        assert!(equal_within_delta(0.0, signal_excess));
+        // Higher frequencies are absorbed more strongly.
+        assert!(min_detectable_ts_db(2000.0, 215.0, 70.0, 10.0, 50.0, 1500.0) > ts);
+    }
+
+    #[test]
+    fn min_detectable_ts_follows_sound_speed(){
+        let temp_c = temperature_from_sound_speed(1500.0, 35.0, 0.0);
+        assert!((sound_speed_seawater(temp_c, 35.0, 0.0) - 1500.0).abs() < 0.0001);
+        assert!(temp_c > 12.0 && temp_c < 14.0);
+
+        // At 10 kHz the magnesium sulfate relaxation absorbs more in colder, slower water.
+        let cold = min_detectable_ts_db(5000.0, 215.0, 70.0, 10.0, 10.0, 1460.0);
+        let warm = min_detectable_ts_db(5000.0, 215.0, 70.0, 10.0, 10.0, 1530.0);
+        assert!(cold > warm);
+    }
+
+    #[test]
+    fn refract_normal_incidence(){
+        let down = Vector::new(0.0, 0.0, -2.0);
+        let refracted = refract(&down, &Vector::new(0.0, 0.0, 1.0), 1500.0, 1550.0).unwrap();
+        assert_eq!(Vector::new(0.0, 0.0, -1.0), refracted);
+        // The orientation of the normal does not matter.
+        assert_eq!(Some(refracted), refract(&down, &Vector::new(0.0, 0.0, -1.0), 1500.0, 1550.0));
+        assert_eq!(None, refract(&Vector::get_world_origin(), &Vector::new(0.0, 0.0, 1.0), 1500.0, 1550.0));
+    }
+
+    #[test]
+    fn refract_into_faster_layer(){
+        // 30° against the normal, crossing a horizontal boundary into faster water.
+        let incident = Vector::new(0.5, 0.0, -(0.75_f64).sqrt());
+        let normal = Vector::new(0.0, 0.0, 1.0);
+        let refracted = refract(&incident, &normal, 1500.0, 1550.0).unwrap();
+        assert!(equal_within_delta(1.0, refracted.magnitude()));
+        assert!(refracted.get_z() < 0.0);
+        assert!(equal_within_delta(0.0, refracted.get_y()));
+        // The sine of the angle against the normal is the horizontal component.
+        assert!(equal_within_delta(0.5 * 1550.0 / 1500.0, refracted.get_x()));
+
+        // Going back into the slower layer restores the incident direction.
+        let back = refract(&refracted, &normal, 1550.0, 1500.0).unwrap();
+        assert!(equal_within_delta(incident.get_x(), back.get_x()));
+        assert!(equal_within_delta(incident.get_z(), back.get_z()));
+    }
+
+    #[test]
+    fn refract_total_internal_reflection(){
+        // 70° against the normal lies beyond the critical angle asin(1500 / 1650) ≈ 65.4°.
+        let incident = Vector::new(70.0_f64.to_radians().sin(), 0.0, -70.0_f64.to_radians().cos());
+        let normal = Vector::new(0.0, 0.0, 1.0);
+        assert_eq!(None, refract(&incident, &normal, 1500.0, 1650.0));
+        assert!(refract(&incident, &normal, 1500.0, 1550.0).is_some());
+    }
+
+    #[test]
+    fn bottom_reflection_loss_grazing(){
+        // Sand is faster than water, so shallow rays below the critical angle of about 24.6° are
+        // totally reflected.
+        assert_eq!(0.0, bottom_reflection_loss_db(0.01, 1650.0, 1900.0));
+        assert_eq!(0.0, bottom_reflection_loss_db(20.0_f64.to_radians(), 1650.0, 1900.0));
+        assert!(bottom_reflection_loss_db(30.0_f64.to_radians(), 1650.0, 1900.0) > 0.0);
+    }
+
+    #[test]
+    fn bottom_reflection_loss_steep(){
+        let steep = bottom_reflection_loss_db(std::f64::consts::FRAC_PI_2, 1650.0, 1900.0);
+        let m = 1900.0 / SEAWATER_DENSITY;
+        let n = NOMINAL_SOUND_SPEED / 1650.0;
+        assert!(equal_within_delta(-20.0 * ((m - n) / (m + n)).log10(), steep));
+        assert!(steep > bottom_reflection_loss_db(40.0_f64.to_radians(), 1650.0, 1900.0));
+        assert!(steep > bottom_reflection_loss_db(0.05, 1650.0, 1900.0));
+
+        // A soft mud bottom slower than water has no critical angle and loses even at grazing
+        // angles.
+        assert!(bottom_reflection_loss_db(0.2, 1480.0, 1400.0) > 0.0);
+    }
+
+    #[test]
+    fn active_detection_range_meets_threshold(){
+        let range = active_detection_range(215.0, 15.0, 70.0, 20.0, 12.0, 1.2);
+        let transmission_loss = 20.0 * range.log10() + 1.2 * range / 1000.0;
+        let signal_excess = 215.0 - 2.0 * transmission_loss + 15.0 - (70.0 - 20.0) - 12.0;
+        assert!(signal_excess.abs() < 0.0001);
+
+        // Without absorption the range follows from 40 log r alone.
+        let range = active_detection_range(210.0, 0.0, 70.0, 20.0, 0.0, 0.0);
+        assert!((range - 10.0_f64.powf(160.0 / 40.0)).abs() < 0.0001);
+    }
+
+    #[test]
+    fn active_detection_range_louder_source(){
+        let mut last = 0.0;
+        for source_level in [190.0, 200.0, 210.0, 220.0, 230.0].iter() {
+            let range = active_detection_range(*source_level, 10.0, 75.0, 15.0, 10.0, 3.0);
+            assert!(range > last);
+            last = range;
+        }
+        // Absorption shortens the range.
+        assert!(active_detection_range(220.0, 10.0, 75.0, 15.0, 10.0, 10.0) < last);
+    }
+
+    #[test]
+    fn active_detection_range_undetectable(){
+        assert_eq!(0.0, active_detection_range(100.0, -20.0, 90.0, 0.0, 20.0, 1.0));
+    }
+
+    #[test]
+    fn active_detection_range_huge_excess(){
+        // Around 10^10 m the spacing of doubles exceeds F64_DELTA, so a tolerance in m alone would
+        // never be reached.
+        let range = active_detection_range(300.0, 40.0, 0.0, 60.0, 0.0, 0.0);
+        assert!((range / 10.0_f64.powf(400.0 / 40.0) - 1.0).abs() < 1e-5);
+
+        let range = active_detection_range(600.0, 40.0, 0.0, 0.0, 0.0, 0.0);
+        assert!((range / 10.0_f64.powf(640.0 / 40.0) - 1.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,127 @@
+//! This module contains functions for beamforming with sonar arrays.
+
+use std::f64::consts::FRAC_PI_2;
+
+use crate::physics::grid::linspace;
+use crate::physics::vectors::{centroid, PolarVec, Vector};
+
+/// Returns the steering directions of a grid over azimuth and elevation as [PolarVec]s with
+/// radius 1.
+///
+/// All angles are in radians. The azimut is measured counterclockwise from east (x) and becomes
+/// phi, the elevation is measured from the horizontal plane upwards and becomes the polar angle
+/// `theta = π/2 - elevation`. az_steps azimuths and el_steps elevations are evenly spaced over
+/// their ranges, including both ends. The direction of azimuth i and elevation j is found at
+/// index `i * el_steps + j`.
+/// # Examples
+/// ```rust
+/// let directions = steering_directions((-FRAC_PI_4, FRAC_PI_4), (-0.1, 0.1), 9, 3);
+/// assert_eq!(27, directions.len());
+/// assert_eq!(PolarVec::new(1.0, -FRAC_PI_4, FRAC_PI_2 + 0.1), directions[0]);
+/// ```
+pub fn steering_directions(az_range: (f64, f64), el_range: (f64, f64), az_steps: usize,
+                           el_steps: usize) -> Vec<PolarVec> {
+    linspace(az_range, az_steps)
+        .flat_map(|azimuth| linspace(el_range, el_steps).map(move |elevation| {
+            PolarVec::new(1.0, azimuth, FRAC_PI_2 - elevation)
+        }))
+        .collect()
+}
+
+/// Returns the arrival time in s of a plane wave at every element of an array relative to its
+/// arrival at the centroid of the array, for delay-and-sum beamforming.
+///
+/// The direction points from the array towards the source and is normalized first. An element at
+/// position e (relative to the centroid) that lies further towards the source receives the wave
+/// earlier, so its delay `-(e · d̂) / c` is negative. A direction without length yields zero
+/// delays.
+/// # Examples
+/// ```rust
+/// let elements = [Vector::new(-1.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0)];
+/// let delays = beamforming_delays(&elements, &Vector::new(1.0, 0.0, 0.0), 1500.0);
+/// assert_eq!(vec![1.0 / 1500.0, -1.0 / 1500.0], delays);
+/// ```
+pub fn beamforming_delays(elements: &[Vector], direction: &Vector, sound_speed_mps: f64) -> Vec<f64> {
+    let direction = match direction.normalize() {
+        Some(direction) => direction,
+        None => return vec![0.0; elements.len()],
+    };
+    let centroid = match centroid(elements) {
+        Some(centroid) => centroid,
+        None => return Vec::new(),
+    };
+    elements.iter()
+        .map(|element| -element.sub(&centroid).dot(&direction) / sound_speed_mps)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::helper_functions::{assert_vec_eq, equal_within_delta};
+    use std::f64::consts::{FRAC_PI_4, PI};
+
+    #[test]
+    fn steering_directions_grid(){
+        let directions = steering_directions((-FRAC_PI_4, FRAC_PI_4), (-0.2, 0.4), 5, 4);
+        assert_eq!(20, directions.len());
+        assert!(directions.iter().all(|direction| direction.get_radius() == 1.0));
+
+        // The corners of the grid match the requested ranges.
+        assert_eq!(PolarVec::new(1.0, -FRAC_PI_4, FRAC_PI_2 + 0.2), directions[0]);
+        assert_eq!(PolarVec::new(1.0, -FRAC_PI_4, FRAC_PI_2 - 0.4), directions[3]);
+        assert_eq!(PolarVec::new(1.0, FRAC_PI_4, FRAC_PI_2 + 0.2), directions[16]);
+        assert_eq!(PolarVec::new(1.0, FRAC_PI_4, FRAC_PI_2 - 0.4), directions[19]);
+
+        // The center azimuth looks straight east.
+        assert_eq!(PolarVec::new(1.0, 0.0, FRAC_PI_2 + 0.2), directions[8]);
+        assert!(equal_within_delta(FRAC_PI_2, directions[9].get_theta_in_rad()));
+        assert!(equal_within_delta(0.2, FRAC_PI_2 - directions[10].get_theta_in_rad()));
+    }
+
+    #[test]
+    fn steering_directions_vertical_corners(){
+        // A sweep from straight down to straight up starts its rows at the nadir.
+        let directions = steering_directions((0.0, FRAC_PI_2), (-FRAC_PI_2, FRAC_PI_2), 2, 3);
+        assert_vec_eq(&Vector::new(0.0, 0.0, -1.0), &directions[0].to_vector());
+        assert_vec_eq(&Vector::new(0.0, 0.0, -1.0), &directions[3].to_vector());
+        assert_vec_eq(&Vector::new(0.0, 1.0, 0.0), &directions[4].to_vector());
+        assert_vec_eq(&Vector::new(0.0, 0.0, 1.0), &directions[5].to_vector());
+    }
+
+    #[test]
+    fn steering_directions_single_step(){
+        let directions = steering_directions((PI, 2.0), (0.3, 1.0), 1, 1);
+        assert_eq!(vec![PolarVec::new(1.0, PI, FRAC_PI_2 - 0.3)], directions);
+        assert!(steering_directions((0.0, 1.0), (0.0, 1.0), 0, 5).is_empty());
+    }
+
+    fn line_array() -> Vec<Vector> {
+        // Eight elements spaced 0.5 m along x, mounted 10 m below a ship.
+        (0..8).map(|i| Vector::new(20.0 + 0.5 * i as f64, 3.0, -10.0)).collect()
+    }
+
+    #[test]
+    fn beamforming_delays_broadside(){
+        let delays = beamforming_delays(&line_array(), &Vector::new(0.0, 1.0, 0.0), 1500.0);
+        assert_eq!(8, delays.len());
+        assert!(delays.iter().all(|delay| equal_within_delta(0.0, *delay)));
+    }
+
+    #[test]
+    fn beamforming_delays_endfire(){
+        let delays = beamforming_delays(&line_array(), &Vector::new(-2.0, 0.0, 0.0), 1500.0);
+        // The element furthest towards the source hears the wave first.
+        assert!(equal_within_delta(-1.75 / 1500.0, delays[0]));
+        assert!(equal_within_delta(1.75 / 1500.0, delays[7]));
+        assert!(equal_within_delta(3.5 / 1500.0, delays[7] - delays[0]));
+        assert!(delays.windows(2).all(|pair| equal_within_delta(0.5 / 1500.0, pair[1] - pair[0])));
+        assert!(equal_within_delta(0.0, delays.iter().sum()));
+    }
+
+    #[test]
+    fn beamforming_delays_degenerate(){
+        assert!(beamforming_delays(&[], &Vector::new(1.0, 0.0, 0.0), 1500.0).is_empty());
+        assert_eq!(vec![0.0; 8], beamforming_delays(&line_array(), &Vector::get_world_origin(), 1500.0));
+    }
+}
@@ -1,4 +1,33 @@
-use super::vectors::Vector;
+use super::matrix::Matrix3;
+use super::quaternion::Quaternion;
+use super::vectors::{PolarVec, Vector};
+use crate::constants::{F64_DELTA, MAX_COORD_SYSTEM_DEPTH};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::sync::OnceLock;
+
+/// Errors that can occur while walking the chain of parent coordinate systems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordSystemError {
+    /// The chain of parent coordinate systems is deeper than the allowed maximum.
+    MaxDepthExceeded { max_depth: usize },
+}
+
+impl Display for CoordSystemError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CoordSystemError::MaxDepthExceeded { max_depth } => {
+                write!(f, "coordinate system is nested deeper than {} levels", max_depth)
+            }
+        }
+    }
+}
+
+impl Error for CoordSystemError {}
 
 pub trait CoordinateSystem: {
     type CoSys: CoordinateSystem;
@@ -10,6 +39,120 @@ pub trait CoordinateSystem: {
     fn get_parent_coord_system(&self) -> Option<&Self::CoSys>;
 
     fn transform_vector_into_world_coords(&self, vec: &Vector) -> Vector;
+
+    fn transform_vector_from_world_coords(&self, vec: &Vector) -> Vector;
+
+    /// Transforms a vector given in this coordinate system into the coordinates of the direct
+    /// parent system. A system without parent returns the vector unchanged.
+    fn transform_vector_into_parent_coords(&self, vec: &Vector) -> Vector {
+        *vec
+    }
+
+    /// Transforms a direction, e.g. a velocity, given in this coordinate system into the
+    /// coordinates of the direct parent system. Unlike a position it is rotated and scaled, but
+    /// not moved by the origin. A system without parent returns the direction unchanged.
+    fn transform_direction_into_parent_coords(&self, dir: &Vector) -> Vector {
+        *dir
+    }
+
+    /// Transforms a direction, e.g. the velocity of a contact measured by a platform, into world
+    /// coordinates by applying the rotation and scale of every system of the chain but none of
+    /// their origins. Without scaled systems in the chain the length of the direction is kept.
+    fn transform_direction_into_world_coords(&self, dir: &Vector) -> Vector where Self: Sized {
+        let mut result = *dir;
+        let mut link: &dyn ParentLink = self;
+        loop {
+            result = link.transform_direction_into_parent(&result);
+            match link.parent_link() {
+                Some(parent) => link = parent,
+                None => return result,
+            }
+        }
+    }
+
+    /// Transforms all vectors given in this coordinate system into world coordinates. The output
+    /// has the same order as the input.
+    fn transform_vectors_into_world_coords(&self, vecs: &[Vector]) -> Vec<Vector> {
+        vecs.iter().map(|vec| self.transform_vector_into_world_coords(vec)).collect()
+    }
+
+    fn transform_polar_vector_into_world_coords(&self, vec: &PolarVec) -> PolarVec {
+        self.transform_vector_into_world_coords(&vec.to_vector()).to_polar_vector()
+    }
+
+    /// Transforms a vector into world coordinates like [transform_vector_into_world_coords], but
+    /// returns an error instead of walking more than [MAX_COORD_SYSTEM_DEPTH] parent systems.
+    /// A coordinate system borrows its parent, so a chain can not contain cycles, but it can still
+    /// be unexpectedly deep.
+    fn try_transform_vector_into_world_coords(&self, vec: &Vector) -> Result<Vector, CoordSystemError> {
+        self.try_transform_vector_into_world_coords_with_max_depth(vec, MAX_COORD_SYSTEM_DEPTH)
+    }
+
+    /// Transforms a vector into world coordinates like [transform_vector_into_world_coords], but
+    /// returns an error if more than max_depth parent systems lie between this system and the
+    /// world. The depth check itself walks at most max_depth parents.
+    fn try_transform_vector_into_world_coords_with_max_depth(&self, vec: &Vector, max_depth: usize)
+        -> Result<Vector, CoordSystemError> {
+        if self.exceeds_depth(max_depth) {
+            return Err(CoordSystemError::MaxDepthExceeded { max_depth });
+        }
+        Ok(self.transform_vector_into_world_coords(vec))
+    }
+
+    /// Returns the number of parent systems between this system and the world, 0 for the world
+    /// system itself.
+    fn get_depth(&self) -> usize {
+        match self.get_parent_coord_system() {
+            None => 0,
+            Some(parent) => parent.get_depth() + 1,
+        }
+    }
+
+    /// Returns the id of the root of the chain of parent systems.
+    fn get_root_id(&self) -> &String {
+        match self.get_parent_coord_system() {
+            None => self.get_id(),
+            Some(parent) => parent.get_root_id(),
+        }
+    }
+
+    /// Returns true if more than max_depth parent systems lie between this system and the world.
+    fn exceeds_depth(&self, max_depth: usize) -> bool {
+        match self.get_parent_coord_system() {
+            None => false,
+            Some(parent) => max_depth == 0 || parent.exceeds_depth(max_depth - 1),
+        }
+    }
+
+    /// Transforms a vector given in this coordinate system into the target coordinate system by
+    /// going through world coordinates.
+    fn transform_vector_to(&self, vec: &Vector, target: &impl CoordinateSystem) -> Vector {
+        target.transform_vector_from_world_coords(&self.transform_vector_into_world_coords(vec))
+    }
+}
+
+/// A single step of a chain of coordinate systems. The parents of a chain all have different
+/// types, so walking the chain in a loop needs them behind the same trait object.
+trait ParentLink {
+    fn parent_link(&self) -> Option<&dyn ParentLink>;
+
+    fn transform_into_parent(&self, vec: &Vector) -> Vector;
+
+    fn transform_direction_into_parent(&self, dir: &Vector) -> Vector;
+}
+
+impl<C: CoordinateSystem> ParentLink for C {
+    fn parent_link(&self) -> Option<&dyn ParentLink> {
+        self.get_parent_coord_system().map(|parent| parent as &dyn ParentLink)
+    }
+
+    fn transform_into_parent(&self, vec: &Vector) -> Vector {
+        self.transform_vector_into_parent_coords(vec)
+    }
+
+    fn transform_direction_into_parent(&self, dir: &Vector) -> Vector {
+        self.transform_direction_into_parent_coords(dir)
+    }
 }
 
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
@@ -36,6 +179,10 @@ impl CoordinateSystem for WorldCoordSystem {
     fn transform_vector_into_world_coords(&self, vec: &Vector) -> Vector {
         *vec
     }
+
+    fn transform_vector_from_world_coords(&self, vec: &Vector) -> Vector {
+        *vec
+    }
 }
 
 impl CoordinateSystem for &WorldCoordSystem {
@@ -56,6 +203,10 @@ impl CoordinateSystem for &WorldCoordSystem {
     fn transform_vector_into_world_coords(&self, vec: &Vector) -> Vector {
         *vec
     }
+
+    fn transform_vector_from_world_coords(&self, vec: &Vector) -> Vector {
+        *vec
+    }
 }
 
 impl WorldCoordSystem {
@@ -65,13 +216,61 @@ impl WorldCoordSystem {
             origin: Vector::get_world_origin()
         }
     }
+
+    /// Returns a world coordinate system that lives for the whole program. It is equal to every
+    /// other world coordinate system and can be borrowed by values that must not depend on the
+    /// lifetime of a caller's world system.
+    pub fn shared() -> &'static WorldCoordSystem {
+        static SHARED_WORLD_COORD_SYSTEM: OnceLock<WorldCoordSystem> = OnceLock::new();
+        SHARED_WORLD_COORD_SYSTEM.get_or_init(WorldCoordSystem::new)
+    }
 }
-#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
+/// A coordinate system that is placed inside a parent coordinate system.
+/// * **origin** is the origin of this system in parent coordinates
+/// * **rotation** is the rotation matrix that turns a vector given in this system into the
+///   orientation of the parent system
+/// * **scale** is the factor by which a vector of this system is scaled in the parent system
+///
+/// The transform into world coordinates composed over the whole chain is computed on first use
+/// and cached. A system can not be changed after construction and borrows its parent immutably,
+/// so the cache never becomes stale.
+#[derive(Debug, Clone)]
 pub struct GeneralCoordSystem<'a, T>
     where T: CoordinateSystem {
     id: String,
     parent_coord_system: &'a T,
-    origin: Vector
+    origin: Vector,
+    rotation: Matrix3,
+    scale: f64,
+    world_transform: OnceLock<(Matrix3, Vector)>
+}
+
+impl<T: CoordinateSystem + PartialEq> PartialEq for GeneralCoordSystem<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id &&
+            self.parent_coord_system == other.parent_coord_system &&
+            self.origin == other.origin &&
+            self.rotation == other.rotation &&
+            self.scale.total_cmp(&other.scale) == Ordering::Equal
+    }
+}
+
+impl<T: CoordinateSystem + Eq> Eq for GeneralCoordSystem<'_, T> {}
+
+impl<T: CoordinateSystem + Ord> PartialOrd for GeneralCoordSystem<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: CoordinateSystem + Ord> Ord for GeneralCoordSystem<'_, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+            .then_with(|| self.parent_coord_system.cmp(other.parent_coord_system))
+            .then_with(|| self.origin.cmp(&other.origin))
+            .then_with(|| self.rotation.cmp(&other.rotation))
+            .then_with(|| self.scale.total_cmp(&other.scale))
+    }
 }
 
 impl<T: CoordinateSystem> CoordinateSystem for GeneralCoordSystem<'_, T>{
@@ -86,39 +285,200 @@ impl<T: CoordinateSystem> CoordinateSystem for GeneralCoordSystem<'_, T>{
     }
 
     fn get_parent_coord_system(&self) -> Option<&Self::CoSys> {
-        Some(&self.parent_coord_system)
+        Some(self.parent_coord_system)
     }
 
+    /// Walks the chain of parent systems in a loop, so deep chains do not cost stack depth.
     fn transform_vector_into_world_coords(&self, vec: &Vector) -> Vector {
-        let temp_vec = &self.transform_vector_into_parent_coords(vec);
-        return match &self.parent_coord_system.get_parent_coord_system() {
-            None => {*temp_vec}
-            Some(x) => {x.transform_vector_into_world_coords(temp_vec)}
+        let mut result = self.transform_vector_into_parent_coords(vec);
+        let mut link: &dyn ParentLink = self.parent_coord_system;
+        loop {
+            result = link.transform_into_parent(&result);
+            match link.parent_link() {
+                Some(parent) => link = parent,
+                None => return result,
+            }
         }
     }
 
+    fn transform_vector_from_world_coords(&self, vec: &Vector) -> Vector {
+        let temp_vec = &self.parent_coord_system.transform_vector_from_world_coords(vec);
+        self.transform_vector_from_parent_coords(temp_vec)
+    }
+
+    fn transform_vector_into_parent_coords(&self, vec: &Vector) -> Vector {
+        let scaled = vec.scale(self.scale);
+        self.rotation.mul_vector(&scaled).add(&self.origin)
+    }
+
+    fn transform_direction_into_parent_coords(&self, dir: &Vector) -> Vector {
+        self.rotation.mul_vector(&dir.scale(self.scale))
+    }
+
+    /// Composes the transforms of the whole chain once and applies the result to all vectors,
+    /// instead of walking the chain for every vector.
+    fn transform_vectors_into_world_coords(&self, vecs: &[Vector]) -> Vec<Vector> {
+        let (linear, offset) = self.composed_world_transform();
+        vecs.iter().map(|vec| linear.mul_vector(vec).add(&offset)).collect()
+    }
 }
 
 impl<T: CoordinateSystem> GeneralCoordSystem<'_, T>{
-    pub fn new(id: String, parent_coord_system: &T, origin: Vector) -> GeneralCoordSystem<T> {
+    /// Creates a new coordinate system that shares the orientation and scale of its parent.
+    pub fn new(id: String, parent_coord_system: &T, origin: Vector) -> GeneralCoordSystem<'_, T> {
+        GeneralCoordSystem::new_with_rotation(id, parent_coord_system, origin, Matrix3::identity())
+    }
+
+    /// Creates a new coordinate system that is rotated against its parent. The rotation matrix
+    /// turns vectors of this system into the orientation of the parent system.
+    pub fn new_with_rotation(id: String, parent_coord_system: &T, origin: Vector,
+                             rotation: Matrix3) -> GeneralCoordSystem<'_, T> {
         GeneralCoordSystem{
             id,
             parent_coord_system,
-            origin
+            origin,
+            rotation,
+            scale: 1.0,
+            world_transform: OnceLock::new()
         }
     }
 
-    fn transform_vector_into_parent_coords(&self, vec: &Vector) -> Vector {
-        let x = &self.origin.get_x() + vec.get_x();
-        let y = &self.origin.get_y() + vec.get_y();
-        let z = &self.origin.get_z() + vec.get_z();
-        Vector::new(x,y,z)
+    /// Creates a new coordinate system for a platform from its heading, pitch and roll in degrees.
+    /// The platform system has its x axis to starboard, its y axis towards the bow and its z axis
+    /// up, so at zero angles it matches an east-north-up parent with the bow pointing north.
+    /// * **heading** turns the bow clockwise from north, seen from above
+    /// * **pitch** raises the bow
+    /// * **roll** lowers the starboard side
+    ///
+    /// The rotations are applied in the intrinsic order Z-X-Y: first the heading around the z
+    /// axis, then the pitch around the new x axis and finally the roll around the new y axis, so
+    /// the rotation matrix is `Rz(-heading) * Rx(pitch) * Ry(roll)`.
+    /// # Examples
+    /// ```rust
+    /// let wcs = WorldCoordSystem::new();
+    /// let ship = GeneralCoordSystem::new_from_euler("ship".to_string(), &wcs, Vector::zero(), 90.0, 0.0, 0.0);
+    /// let bow = ship.transform_vector_into_world_coords(&Vector::unit_y());
+    /// assert!((bow.get_x() - 1.0).abs() < F64_DELTA);
+    /// ```
+    pub fn new_from_euler(id: String, parent_coord_system: &T, origin: Vector, heading_deg: f64,
+                          pitch_deg: f64, roll_deg: f64) -> GeneralCoordSystem<'_, T> {
+        let heading = Matrix3::from_euler(-heading_deg.to_radians(), 0.0, 0.0);
+        let pitch = Matrix3::from_euler(0.0, 0.0, pitch_deg.to_radians());
+        let roll = Matrix3::from_euler(0.0, roll_deg.to_radians(), 0.0);
+        let rotation = heading.mul_matrix(&pitch).mul_matrix(&roll);
+        GeneralCoordSystem::new_with_rotation(id, parent_coord_system, origin, rotation)
+    }
+
+    /// Creates a new coordinate system that shares the orientation of its parent but whose units
+    /// differ by scale, e.g. 0.3048 for a system in feet placed in a system in meters.
+    pub fn new_with_scale(id: String, parent_coord_system: &T, origin: Vector,
+                          scale: f64) -> GeneralCoordSystem<'_, T> {
+        GeneralCoordSystem{
+            id,
+            parent_coord_system,
+            origin,
+            rotation: Matrix3::identity(),
+            scale,
+            world_transform: OnceLock::new()
+        }
+    }
+
+    /// Returns the rotation matrix of this coordinate system.
+    pub fn get_rotation(&self) -> &Matrix3 {
+        &self.rotation
+    }
+
+    /// Returns the scale of this coordinate system relative to its parent.
+    pub fn get_scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Transforms all vectors given in this coordinate system into world coordinates like
+    /// [transform_vectors_into_world_coords], but spreads the work over all cores. The output has
+    /// the same order as the input.
+    #[cfg(feature = "rayon")]
+    pub fn par_transform_vectors_into_world_coords(&self, vecs: &[Vector]) -> Vec<Vector> {
+        let (linear, offset) = self.composed_world_transform();
+        vecs.par_iter().map(|vec| linear.mul_vector(vec).add(&offset)).collect()
+    }
+
+    /// Returns the transform into world coordinates as a matrix and an offset, so that a vector v
+    /// of this system lies at matrix * v + offset in the world. It is computed once per system.
+    fn composed_world_transform(&self) -> (Matrix3, Vector) {
+        *self.world_transform.get_or_init(|| self.compute_world_transform())
+    }
+
+    /// Computes the transform returned by [composed_world_transform]. Every system of the chain
+    /// only rotates, scales and translates, so the composed transform is fixed by the images of
+    /// the origin and the three unit axes.
+    fn compute_world_transform(&self) -> (Matrix3, Vector) {
+        let offset = self.transform_vector_into_world_coords(&Vector::zero());
+        let x = self.transform_vector_into_world_coords(&Vector::unit_x()).sub(&offset);
+        let y = self.transform_vector_into_world_coords(&Vector::unit_y()).sub(&offset);
+        let z = self.transform_vector_into_world_coords(&Vector::unit_z()).sub(&offset);
+        let linear = Matrix3::new([[x.get_x(), y.get_x(), z.get_x()],
+                                   [x.get_y(), y.get_y(), z.get_y()],
+                                   [x.get_z(), y.get_z(), z.get_z()]]);
+        (linear, offset)
+    }
+
+    fn transform_vector_from_parent_coords(&self, vec: &Vector) -> Vector {
+        let local = vec.sub(&self.origin);
+        self.rotation.transpose().mul_vector(&local).scale(1.0 / self.scale)
     }
 }
 
+/// Returns the smallest rotation that turns the up vector measured by a tilted sensor onto the
+/// world up axis +z, or None if the measured vector has no length. Applying the rotation to
+/// vectors measured by the sensor levels them.
+/// # Examples
+/// ```rust
+/// let rotation = level_rotation(&Vector::new(0.0, 1.0, 1.0)).unwrap();
+/// let up = rotation.rotate_vector(&Vector::new(0.0, 1.0, 1.0).normalize().unwrap());
+/// assert!((up.get_z() - 1.0).abs() < F64_DELTA);
+/// ```
+pub fn level_rotation(measured_up: &Vector) -> Option<Quaternion> {
+    let up = measured_up.normalize()?;
+    let world_up = Vector::new(0.0, 0.0, 1.0);
+    let cos = up.dot(&world_up);
+    if cos <= -1.0 + F64_DELTA {
+        // Upside down: every horizontal axis gives a smallest rotation, so take x.
+        return Some(Quaternion::new(0.0, 1.0, 0.0, 0.0));
+    }
+    // (1 + cos θ, up × world_up) is the rotation quaternion by θ scaled with 2cos(θ/2), so it
+    // only needs to be normalized.
+    let axis = up.cross(&world_up);
+    let w = 1.0 + cos;
+    let norm = (w * w + axis.dot(&axis)).sqrt();
+    Some(Quaternion::new(w / norm, axis.get_x() / norm, axis.get_y() / norm, axis.get_z() / norm))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::helper_functions::{assert_vec_eq, equal_within_delta};
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    /// Builds a chain with one GeneralCoordSystem per token below $parent, each shifted by 1 m
+    /// along x, and evaluates $body with the innermost system bound to $leaf.
+    macro_rules! nested_chain {
+        ($parent:expr, $leaf:ident => $body:expr; $head:tt $($tail:tt)*) => {{
+            let child = GeneralCoordSystem::new("child".to_string(), $parent, Vector::new(1.0, 0.0, 0.0));
+            nested_chain!(&child, $leaf => $body; $($tail)*)
+        }};
+        ($parent:expr, $leaf:ident => $body:expr;) => {{
+            let $leaf = $parent;
+            $body
+        }};
+    }
+
+    /// Transforms into world coordinates by recursing once per parent system.
+    fn recursive_world_coords<C: CoordinateSystem>(cs: &C, vec: &Vector) -> Vector {
+        match cs.get_parent_coord_system() {
+            None => cs.transform_vector_into_parent_coords(vec),
+            Some(parent) => recursive_world_coords(parent, &cs.transform_vector_into_parent_coords(vec)),
+        }
+    }
 
     #[test]
     fn default_creation(){
@@ -128,6 +488,12 @@ mod tests {
         assert_eq!(None, cs.get_parent_coord_system());
     }
 
+    #[test]
+    fn shared_world(){
+        assert_eq!(&WorldCoordSystem::new(), WorldCoordSystem::shared());
+        assert!(std::ptr::eq(WorldCoordSystem::shared(), WorldCoordSystem::shared()));
+    }
+
     #[test]
     fn eq(){
         let a = WorldCoordSystem::new();
@@ -146,5 +512,333 @@ mod tests {
         println!("{:?}", origin);
         println!("{:?}", gcs);
     }
-}
 
+    #[test]
+    fn transform_with_translation(){
+        let wcs = WorldCoordSystem::new();
+        let gcs = GeneralCoordSystem::new("gcs".to_string(), &wcs, Vector::new(10.0, 5.0, -2.0));
+
+        let vec = Vector::new(1.0, 2.0, 3.0);
+        assert_eq!(Vector::new(11.0, 7.0, 1.0), gcs.transform_vector_into_world_coords(&vec));
+    }
+
+    #[test]
+    fn transform_with_rotation(){
+        // 90° yaw: the x axis of the child points along the y axis of the parent.
+        let yaw_90 = Matrix3::new([[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]);
+        let wcs = WorldCoordSystem::new();
+        let gcs = GeneralCoordSystem::new_with_rotation("gcs".to_string(), &wcs,
+                                                        Vector::new(10.0, 0.0, 0.0), yaw_90);
+
+        assert_eq!(Vector::new(10.0, 1.0, 0.0),
+                   gcs.transform_vector_into_world_coords(&Vector::new(1.0, 0.0, 0.0)));
+        assert_eq!(Vector::new(8.0, 0.0, 3.0),
+                   gcs.transform_vector_into_world_coords(&Vector::new(0.0, 2.0, 3.0)));
+    }
+
+    #[test]
+    fn transform_through_chain(){
+        let yaw_90 = Matrix3::new([[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]);
+        let wcs = WorldCoordSystem::new();
+        let platform = GeneralCoordSystem::new_with_rotation("platform".to_string(), &wcs,
+                                                             Vector::new(100.0, 0.0, 0.0), yaw_90);
+        let sensor = GeneralCoordSystem::new("sensor".to_string(), &platform,
+                                             Vector::new(2.0, 0.0, -1.0));
+
+        assert_eq!(Vector::new(100.0, 3.0, -1.0),
+                   sensor.transform_vector_into_world_coords(&Vector::new(1.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn transform_with_euler_angles(){
+        let wcs = WorldCoordSystem::new();
+        let origin = Vector::new(100.0, 50.0, 0.0);
+        let east = GeneralCoordSystem::new_from_euler("east".to_string(), &wcs, origin, 90.0, 0.0, 0.0);
+        assert_vec_eq(&Vector::new(110.0, 50.0, 0.0), &east.transform_vector_into_world_coords(&Vector::new(0.0, 10.0, 0.0)));
+        // Starboard of a ship heading east is south.
+        assert_vec_eq(&Vector::new(100.0, 40.0, 0.0), &east.transform_vector_into_world_coords(&Vector::new(10.0, 0.0, 0.0)));
+
+        let bow_up = GeneralCoordSystem::new_from_euler("bow_up".to_string(), &wcs, origin, 0.0, 30.0, 0.0);
+        assert_vec_eq(&Vector::new(100.0, 50.0 + 0.75_f64.sqrt(), 0.5),
+                      &bow_up.transform_vector_into_world_coords(&Vector::unit_y()));
+
+        let rolled = GeneralCoordSystem::new_from_euler("rolled".to_string(), &wcs, origin, 0.0, 0.0, 30.0);
+        assert_vec_eq(&Vector::new(100.0 + 0.75_f64.sqrt(), 50.0, -0.5),
+                      &rolled.transform_vector_into_world_coords(&Vector::unit_x()));
+
+        // Heading is applied first: a ship heading east with its bow raised points east and up.
+        let combined = GeneralCoordSystem::new_from_euler("combined".to_string(), &wcs,
+                                                          Vector::zero(), 90.0, 30.0, 45.0);
+        assert_vec_eq(&Vector::new(0.75_f64.sqrt(), 0.0, 0.5),
+                      &combined.transform_vector_into_world_coords(&Vector::unit_y()));
+        assert!(equal_within_delta(1.0, combined.get_rotation().determinant()));
+    }
+
+    #[test]
+    fn transform_direction(){
+        let wcs = WorldCoordSystem::new();
+        let ship = GeneralCoordSystem::new_from_euler("ship".to_string(), &wcs,
+                                                      Vector::new(500.0, 200.0, 0.0), 90.0, 0.0, 0.0);
+        let sonar = GeneralCoordSystem::new_from_euler("sonar".to_string(), &ship,
+                                                       Vector::new(0.0, 30.0, -5.0), 0.0, -20.0, 5.0);
+
+        // A contact moving forward at 6 m/s relative to a ship heading east moves east.
+        let velocity = ship.transform_direction_into_world_coords(&Vector::new(0.0, 6.0, 0.0));
+        assert_vec_eq(&Vector::new(6.0, 0.0, 0.0), &velocity);
+
+        let velocity = Vector::new(3.0, -4.0, 1.5);
+        let world_velocity = sonar.transform_direction_into_world_coords(&velocity);
+        assert!(equal_within_delta(velocity.magnitude(), world_velocity.magnitude()));
+        // A direction is the difference of two transformed positions.
+        let start = Vector::new(7.0, 8.0, -9.0);
+        let difference = sonar.transform_vector_into_world_coords(&start.add(&velocity))
+            .sub(&sonar.transform_vector_into_world_coords(&start));
+        assert_vec_eq(&difference, &world_velocity);
+    }
+
+    #[test]
+    fn transform_direction_translation_only(){
+        let wcs = WorldCoordSystem::new();
+        let moved = GeneralCoordSystem::new("moved".to_string(), &wcs, Vector::new(-40.0, 25.0, -12.0));
+        let nested = GeneralCoordSystem::new("nested".to_string(), &moved, Vector::new(3.0, 3.0, 3.0));
+        let dir = Vector::new(1.0, -2.0, 0.5);
+        assert_eq!(dir, nested.transform_direction_into_world_coords(&dir));
+        assert_eq!(dir, wcs.transform_direction_into_world_coords(&dir));
+
+        let feet = GeneralCoordSystem::new_with_scale("feet".to_string(), &wcs, Vector::zero(), 0.3048);
+        assert_eq!(Vector::new(0.3048, 0.0, 0.0), feet.transform_direction_into_world_coords(&Vector::unit_x()));
+    }
+
+    #[test]
+    fn transform_with_scale(){
+        let wcs = WorldCoordSystem::new();
+        let gcs = GeneralCoordSystem::new_with_scale("gcs".to_string(), &wcs,
+                                                     Vector::get_world_origin(), 2.0);
+        assert_eq!(2.0, gcs.get_scale());
+
+        let vec = Vector::new(3.0, 0.0, 4.0);
+        let world_vec = gcs.transform_vector_into_world_coords(&vec);
+        assert_eq!(Vector::new(6.0, 0.0, 8.0), world_vec);
+        assert_eq!(10.0, world_vec.to_polar_vector().get_radius());
+
+        let gcs = GeneralCoordSystem::new_with_scale("gcs".to_string(), &wcs,
+                                                     Vector::new(1.0, 1.0, 1.0), 2.0);
+        assert_eq!(Vector::new(7.0, 1.0, 9.0), gcs.transform_vector_into_world_coords(&vec));
+    }
+
+    #[test]
+    fn transform_from_world(){
+        let wcs = WorldCoordSystem::new();
+        let vec = Vector::new(1.0, 2.0, 3.0);
+        assert_eq!(vec, wcs.transform_vector_from_world_coords(&vec));
+
+        let gcs = GeneralCoordSystem::new("gcs".to_string(), &wcs, Vector::new(10.0, 5.0, -2.0));
+        assert_eq!(Vector::new(-9.0, -3.0, 5.0), gcs.transform_vector_from_world_coords(&vec));
+    }
+
+    #[test]
+    fn transform_round_trip(){
+        let yaw_90 = Matrix3::new([[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]);
+        let wcs = WorldCoordSystem::new();
+        let platform = GeneralCoordSystem::new_with_rotation("platform".to_string(), &wcs,
+                                                             Vector::new(100.0, -20.0, 3.0), yaw_90);
+        let sensor = GeneralCoordSystem::new_with_scale("sensor".to_string(), &platform,
+                                                        Vector::new(2.0, 0.5, -1.0), 0.3048);
+
+        let world_vec = Vector::new(-42.0, 17.5, -300.0);
+        let local_vec = sensor.transform_vector_from_world_coords(&world_vec);
+        assert_vec_eq(&world_vec, &sensor.transform_vector_into_world_coords(&local_vec));
+
+        let local_vec = Vector::new(5.0, -6.0, 7.0);
+        let world_vec = sensor.transform_vector_into_world_coords(&local_vec);
+        assert_vec_eq(&local_vec, &sensor.transform_vector_from_world_coords(&world_vec));
+    }
+
+    #[test]
+    fn transform_polar_vector(){
+        let wcs = WorldCoordSystem::new();
+        let contact = PolarVec::new(10.0, FRAC_PI_2, FRAC_PI_2);
+        assert_eq!(contact, wcs.transform_polar_vector_into_world_coords(&contact));
+
+        // A contact 10 m north of a platform that is 10 m east of the world origin.
+        let gcs = GeneralCoordSystem::new("gcs".to_string(), &wcs, Vector::new(10.0, 0.0, 0.0));
+        let world_contact = gcs.transform_polar_vector_into_world_coords(&contact);
+        assert_eq!(PolarVec::new(200.0_f64.sqrt(), FRAC_PI_4, FRAC_PI_2), world_contact);
+
+        // A contact 10 m south of a platform that is 10 m west of the world origin lies south-west,
+        // with the azimut kept in 0..2*pi.
+        let gcs = GeneralCoordSystem::new("gcs".to_string(), &wcs, Vector::new(-10.0, 0.0, 0.0));
+        let contact = PolarVec::new(10.0, PI + FRAC_PI_2, FRAC_PI_2);
+        let world_contact = gcs.transform_polar_vector_into_world_coords(&contact);
+        assert!(equal_within_delta(PI + FRAC_PI_4, world_contact.get_phi_in_rad()));
+        assert_eq!(PolarVec::new(200.0_f64.sqrt(), PI + FRAC_PI_4, FRAC_PI_2), world_contact);
+    }
+
+    #[test]
+    fn transform_between_siblings(){
+        let yaw_90 = Matrix3::new([[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]);
+        let wcs = WorldCoordSystem::new();
+        let ship = GeneralCoordSystem::new("ship".to_string(), &wcs, Vector::new(50.0, 50.0, 0.0));
+        let sonar_a = GeneralCoordSystem::new("sonar_a".to_string(), &ship,
+                                              Vector::new(10.0, 0.0, -5.0));
+        let sonar_b = GeneralCoordSystem::new_with_rotation("sonar_b".to_string(), &ship,
+                                                            Vector::new(-10.0, 0.0, -5.0), yaw_90);
+
+        // sonar_b is yawed by 90°, so the x axis of the ship is its negative y axis.
+        let contact = Vector::new(-10.0, 3.0, 1.0);
+        let in_b = sonar_a.transform_vector_to(&contact, &sonar_b);
+        assert_vec_eq(&Vector::new(3.0, -10.0, 1.0), &in_b);
+        assert_vec_eq(&contact, &sonar_b.transform_vector_to(&in_b, &sonar_a));
+
+        let in_world = sonar_a.transform_vector_to(&contact, &wcs);
+        assert_vec_eq(&sonar_a.transform_vector_into_world_coords(&contact), &in_world);
+    }
+
+    #[test]
+    fn try_transform_long_chain(){
+        let wcs = WorldCoordSystem::new();
+        let vec = Vector::get_world_origin();
+
+        // 32 levels below the world system.
+        let result = nested_chain!(&wcs, leaf => leaf.try_transform_vector_into_world_coords(&vec);
+            x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x);
+        assert_eq!(Ok(Vector::new(32.0, 0.0, 0.0)), result);
+    }
+
+    #[test]
+    fn cached_world_transform(){
+        let wcs = WorldCoordSystem::new();
+        let ship = GeneralCoordSystem::new_with_rotation("ship".to_string(), &wcs,
+                                                         Vector::new(-80.0, 35.0, 0.0),
+                                                         Matrix3::from_euler(2.1, -0.04, 0.1));
+        let sonar = GeneralCoordSystem::new_with_scale("sonar".to_string(), &ship,
+                                                       Vector::new(3.0, -1.5, -6.0), 0.3048);
+        assert!(sonar.world_transform.get().is_none());
+
+        let fresh = sonar.compute_world_transform();
+        assert_eq!(fresh, sonar.composed_world_transform());
+        assert_eq!(Some(&fresh), sonar.world_transform.get());
+        assert_eq!(fresh, sonar.composed_world_transform());
+        assert_eq!(fresh, sonar.clone().composed_world_transform());
+
+        let vec = Vector::new(10.0, 20.0, -30.0);
+        assert_vec_eq(&sonar.transform_vector_into_world_coords(&vec),
+                      &sonar.transform_vectors_into_world_coords(&[vec])[0]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_batch_transform(){
+        let wcs = WorldCoordSystem::new();
+        let ship = GeneralCoordSystem::new_with_rotation("ship".to_string(), &wcs,
+                                                         Vector::new(1200.0, -300.0, 0.0),
+                                                         Matrix3::from_euler(-0.4, 0.02, 0.03));
+        let sonar = GeneralCoordSystem::new_with_scale("sonar".to_string(), &ship,
+                                                       Vector::new(0.0, 4.0, -8.0), 0.3048);
+
+        let vecs: Vec<Vector> = (0..100_000)
+            .map(|i| Vector::new((i % 317) as f64, (i % 89) as f64 - 44.0, -(i as f64) / 100.0))
+            .collect();
+        assert_eq!(sonar.transform_vectors_into_world_coords(&vecs),
+                   sonar.par_transform_vectors_into_world_coords(&vecs));
+        assert!(sonar.par_transform_vectors_into_world_coords(&[]).is_empty());
+    }
+
+    #[test]
+    fn iterative_transform_deep_chain(){
+        let wcs = WorldCoordSystem::new();
+        let yaw = Matrix3::from_euler(0.3, 0.0, 0.0);
+        let tilted = GeneralCoordSystem::new_with_rotation("tilted".to_string(), &wcs,
+                                                           Vector::new(0.0, 0.0, -10.0), yaw);
+        let vec = Vector::new(2.0, -1.0, 0.5);
+
+        // 100 levels below the tilted system.
+        let (iterative, recursive) = nested_chain!(&tilted, leaf => (
+            leaf.transform_vector_into_world_coords(&vec),
+            recursive_world_coords(leaf, &vec)
+        ); x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+           x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x);
+        assert_eq!(recursive, iterative);
+        assert_vec_eq(&tilted.transform_vector_into_world_coords(&Vector::new(102.0, -1.0, 0.5)), &iterative);
+    }
+
+    #[test]
+    fn try_transform_at_depth_limit(){
+        let wcs = WorldCoordSystem::new();
+        let vec = Vector::get_world_origin();
+
+        assert_eq!(Ok(vec), wcs.try_transform_vector_into_world_coords_with_max_depth(&vec, 0));
+
+        // A chain that is exactly as deep as the limit is still transformed, one level more fails.
+        let (at_limit, above_limit) = nested_chain!(&wcs, leaf => (
+            leaf.try_transform_vector_into_world_coords_with_max_depth(&vec, 8),
+            leaf.try_transform_vector_into_world_coords_with_max_depth(&vec, 7)
+        ); x x x x x x x x);
+        assert_eq!(Ok(Vector::new(8.0, 0.0, 0.0)), at_limit);
+        assert_eq!(Err(CoordSystemError::MaxDepthExceeded { max_depth: 7 }), above_limit);
+        assert_eq!("coordinate system is nested deeper than 7 levels", above_limit.unwrap_err().to_string());
+    }
+
+    #[test]
+    fn depth_and_root(){
+        let wcs = WorldCoordSystem::new();
+        let ship = GeneralCoordSystem::new("ship".to_string(), &wcs, Vector::new(50.0, 50.0, 0.0));
+        let sonar = GeneralCoordSystem::new("sonar".to_string(), &ship, Vector::new(10.0, 0.0, -5.0));
+
+        assert_eq!(0, wcs.get_depth());
+        assert_eq!(1, ship.get_depth());
+        assert_eq!(2, sonar.get_depth());
+
+        assert_eq!(&"wcs".to_string(), wcs.get_root_id());
+        assert_eq!(&"wcs".to_string(), ship.get_root_id());
+        assert_eq!(&"wcs".to_string(), sonar.get_root_id());
+    }
+
+    #[test]
+    fn batch_transform_matches_single(){
+        let wcs = WorldCoordSystem::new();
+        let ship = GeneralCoordSystem::new_with_rotation("ship".to_string(), &wcs,
+                                                         Vector::new(500.0, -250.0, 0.0),
+                                                         Matrix3::from_euler(0.7, 0.05, -0.02));
+        let mount = GeneralCoordSystem::new_with_scale("mount".to_string(), &ship,
+                                                       Vector::new(12.0, 0.0, -4.0), 0.3048);
+        let sonar = GeneralCoordSystem::new_with_rotation("sonar".to_string(), &mount,
+                                                          Vector::new(1.0, 2.0, -3.0),
+                                                          Matrix3::from_euler(-1.2, 0.3, 0.0));
+
+        let vecs = [Vector::zero(), Vector::new(100.0, 0.0, -20.0), Vector::new(-3.5, 42.0, 7.0),
+                    Vector::new(0.1, -0.2, -250.0)];
+        let batch = sonar.transform_vectors_into_world_coords(&vecs);
+        assert_eq!(vecs.len(), batch.len());
+        for (vec, world_vec) in vecs.iter().zip(batch.iter()) {
+            assert_vec_eq(&sonar.transform_vector_into_world_coords(vec), world_vec);
+        }
+        assert_eq!(vecs.to_vec(), wcs.transform_vectors_into_world_coords(&vecs));
+        assert!(sonar.transform_vectors_into_world_coords(&[]).is_empty());
+    }
+
+    #[test]
+    fn level_rotation_tilted_mount(){
+        let world_up = Vector::new(0.0, 0.0, 1.0);
+        for measured_up in [Vector::new(0.2, -0.1, 0.97), Vector::new(3.0, 4.0, 1.0),
+                            Vector::new(1.0, 0.0, 0.0), Vector::new(0.1, 0.0, -1.0)].iter() {
+            let rotation = level_rotation(measured_up).unwrap();
+            assert!(equal_within_delta(1.0, rotation.magnitude()));
+            assert_vec_eq(&world_up, &rotation.rotate_vector(&measured_up.normalize().unwrap()));
+        }
+
+        // A sideways tilt is undone by a rotation around a horizontal axis only.
+        let rotation = level_rotation(&Vector::new(0.0, 1.0, 1.0)).unwrap();
+        assert!(equal_within_delta(0.0, rotation.get_vector().get_z()));
+        assert!(equal_within_delta((FRAC_PI_4 / 2.0).cos(), rotation.get_w()));
+    }
+
+    #[test]
+    fn level_rotation_special_cases(){
+        let world_up = Vector::new(0.0, 0.0, 1.0);
+        assert_eq!(Some(Quaternion::identity()), level_rotation(&Vector::new(0.0, 0.0, 5.0)));
+        let flipped = level_rotation(&Vector::new(0.0, 0.0, -2.0)).unwrap();
+        assert_vec_eq(&world_up, &flipped.rotate_vector(&Vector::new(0.0, 0.0, -1.0)));
+        assert_eq!(None, level_rotation(&Vector::get_world_origin()));
+    }
+}
@@ -0,0 +1,87 @@
+//! This module contains conversions from geodetic coordinates into the local cartesian frame of
+//! the crate.
+
+use crate::physics::vectors::Vector;
+
+/// The semi-major axis of the WGS84 ellipsoid in m.
+const WGS84_A: f64 = 6_378_137.0;
+/// The first eccentricity squared of the WGS84 ellipsoid.
+const WGS84_E2: f64 = 6.694_379_990_14e-3;
+
+/// Returns the position of a contact given by latitude and longitude (in degrees) and depth (in m,
+/// positive down) in a local east-north-up frame whose origin lies on the sea surface at the
+/// reference latitude and longitude. East maps to x, north to y and up to z, so the depth ends up
+/// as a negative z.
+///
+/// The conversion uses a local tangent plane: the difference in latitude is scaled with the
+/// meridional radius of curvature of the WGS84 ellipsoid at the reference latitude, the difference
+/// in longitude with the prime vertical radius of curvature at the mean latitude of contact and
+/// reference, where the parallels in between have their average length. This keeps the error of
+/// the horizontal distance to the reference below 0.1 m up to about 10 km and below 0.02 % up to
+/// about 100 km. The curvature of the earth is neglected otherwise, so the direction to a contact
+/// that is offset both north and east turns by half the convergence of the meridians: at 45°
+/// latitude a contact 10 km north and 10 km east is placed about 11 m beside its true position.
+/// The approximation breaks down close to the poles and across the antimeridian.
+/// # Examples
+/// ```rust
+/// let position = geodetic_to_enu(54.0, 10.01, 20.0, 54.0, 10.0);
+/// assert!((position.get_x() - 655.8).abs() < 0.1);
+/// assert_eq!(-20.0, position.get_z());
+/// ```
+pub fn geodetic_to_enu(lat_deg: f64, lon_deg: f64, depth_m: f64, ref_lat_deg: f64, ref_lon_deg: f64) -> Vector {
+    let ref_lat = ref_lat_deg.to_radians();
+    let mean_lat = ((lat_deg + ref_lat_deg) / 2.0).to_radians();
+    let meridional_radius = WGS84_A * (1.0 - WGS84_E2) / (1.0 - WGS84_E2 * ref_lat.sin().powi(2)).powf(1.5);
+    let prime_vertical_radius = WGS84_A / (1.0 - WGS84_E2 * mean_lat.sin().powi(2)).sqrt();
+
+    let east = (lon_deg - ref_lon_deg).to_radians() * prime_vertical_radius * mean_lat.cos();
+    let north = (lat_deg - ref_lat_deg).to_radians() * meridional_radius;
+    Vector::new(east, north, -depth_m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn due_east(){
+        // On the equator a degree of longitude spans 2 * pi * a / 360.
+        let position = geodetic_to_enu(0.0, 0.01, 0.0, 0.0, 0.0);
+        assert!((position.get_x() - 1113.195).abs() < 0.001);
+        assert_eq!(0.0, position.get_y());
+        assert_eq!(0.0, position.get_z());
+
+        // Further north the meridians converge.
+        let position = geodetic_to_enu(60.0, 5.01, 0.0, 60.0, 5.0);
+        assert!((position.get_x() - 558.0).abs() < 0.001);
+        assert_eq!(0.0, position.get_y());
+    }
+
+    #[test]
+    fn due_north(){
+        // A minute of latitude is close to a nautical mile of 1852 m at mid latitudes.
+        let position = geodetic_to_enu(45.0 + 1.0 / 60.0, -30.0, 0.0, 45.0, -30.0);
+        assert_eq!(0.0, position.get_x());
+        assert!((position.get_y() - 1852.0).abs() < 1.0);
+
+        let south = geodetic_to_enu(45.0 - 1.0 / 60.0, -30.0, 0.0, 45.0, -30.0);
+        assert!((south.get_y() + position.get_y()).abs() < 0.001);
+    }
+
+    #[test]
+    fn diagonal_offset(){
+        // About 10 km north and 10 km east at 45° latitude. The geodesic to the contact is
+        // 14147.54 m long with an azimut of 44.966° from north.
+        let position = geodetic_to_enu(45.09, 10.127, 0.0, 45.0, 10.0);
+        assert!((position.magnitude() - 14147.54).abs() < 0.1);
+
+        let true_position = Vector::new(9997.86, 10009.78, 0.0);
+        assert!(position.sub(&true_position).magnitude() < 12.0);
+    }
+
+    #[test]
+    fn depth_points_down(){
+        let position = geodetic_to_enu(10.0, 10.0, 150.0, 10.0, 10.0);
+        assert_eq!(Vector::new(0.0, 0.0, -150.0), position);
+    }
+}
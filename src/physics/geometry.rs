@@ -0,0 +1,163 @@
+//! This module contains geometric tests between rays, segments and simple bodies.
+
+use crate::physics::vectors::Vector;
+
+/// Returns the distances (near, far) along a ray from origin in direction dir at which the ray
+/// enters and leaves a sphere, or None if the ray misses the sphere. The direction is normalized,
+/// so the distances are in the units of the positions.
+///
+/// A ray that only touches the sphere returns the same distance twice. If the origin lies inside
+/// the sphere, near is negative because the entry point lies behind the origin. A sphere that lies
+/// completely behind the origin, as well as a direction without length, results in None.
+/// # Examples
+/// ```rust
+/// let hit = intersect_ray_sphere(&Vector::get_world_origin(), &Vector::new(2.0, 0.0, 0.0),
+///                                &Vector::new(10.0, 0.0, 0.0), 1.0);
+/// assert_eq!(Some((9.0, 11.0)), hit);
+/// ```
+pub fn intersect_ray_sphere(origin: &Vector, dir: &Vector, center: &Vector, radius: f64) -> Option<(f64, f64)> {
+    let dir = dir.normalize()?;
+    let to_origin = origin.sub(center);
+    let b = to_origin.dot(&dir);
+    let c = to_origin.dot(&to_origin) - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    let (near, far) = (-b - root, -b + root);
+    if far < 0.0 {
+        return None;
+    }
+    Some((near, far))
+}
+
+/// Returns true if the line of sight from a to b is blocked by a sphere, i.e. the segment between
+/// both points passes within radius of the center. A segment that starts or ends inside the
+/// sphere counts as blocked, a sphere beyond either end of the segment does not. If a and b
+/// coincide, the sight is blocked if the point lies within the sphere.
+/// # Examples
+/// ```rust
+/// let blocked = is_occluded_by_sphere(&Vector::get_world_origin(), &Vector::new(20.0, 0.0, 0.0),
+///                                     &Vector::new(10.0, 1.0, 0.0), 2.0);
+/// assert!(blocked);
+/// ```
+pub fn is_occluded_by_sphere(a: &Vector, b: &Vector, center: &Vector, radius: f64) -> bool {
+    let segment = b.sub(a);
+    let length = segment.magnitude();
+    if length == 0.0 {
+        return a.sub(center).magnitude() <= radius;
+    }
+    match intersect_ray_sphere(a, &segment, center, radius) {
+        Some((near, _)) => near <= length,
+        None => false,
+    }
+}
+
+/// Returns the grazing angle in rad (in range 0..pi/2) between a ray direction and the horizontal
+/// plane, e.g. of a ray hitting a flat seabed.
+///
+/// The crate uses east-north-up coordinates, so a downgoing ray has a negative z component. The
+/// angle is computed from the magnitude of z only, so an upgoing ray towards the sea surface gets
+/// its grazing angle against the surface. A direction without length returns 0.
+/// # Examples
+/// ```rust
+/// let angle = grazing_angle(&Vector::new(1.0, 0.0, -1.0));
+/// assert!(equal_within_delta(FRAC_PI_4, angle));
+/// ```
+pub fn grazing_angle(ray_dir: &Vector) -> f64 {
+    match ray_dir.normalize() {
+        Some(dir) => dir.get_z().abs().min(1.0).asin(),
+        None => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::helper_functions::equal_within_delta;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    #[test]
+    fn ray_sphere_direct_hit(){
+        let hit = intersect_ray_sphere(&Vector::new(0.0, 0.0, -10.0), &Vector::new(0.0, 5.0, 0.0),
+                                       &Vector::new(0.0, 100.0, -10.0), 20.0);
+        assert_eq!(Some((80.0, 120.0)), hit);
+    }
+
+    #[test]
+    fn ray_sphere_miss(){
+        let center = Vector::new(0.0, 100.0, -10.0);
+        assert_eq!(None, intersect_ray_sphere(&Vector::get_world_origin(), &Vector::new(1.0, 0.0, 0.0),
+                                              &center, 20.0));
+        // The sphere lies behind the origin.
+        assert_eq!(None, intersect_ray_sphere(&Vector::get_world_origin(), &Vector::new(0.0, -1.0, 0.0),
+                                              &center, 20.0));
+        assert_eq!(None, intersect_ray_sphere(&Vector::get_world_origin(), &Vector::get_world_origin(),
+                                              &center, 20.0));
+    }
+
+    #[test]
+    fn ray_sphere_tangent(){
+        let hit = intersect_ray_sphere(&Vector::new(-50.0, 5.0, 0.0), &Vector::new(1.0, 0.0, 0.0),
+                                       &Vector::get_world_origin(), 5.0);
+        assert_eq!(Some((50.0, 50.0)), hit);
+    }
+
+    #[test]
+    fn ray_sphere_origin_inside(){
+        let (near, far) = intersect_ray_sphere(&Vector::new(1.0, 0.0, 0.0), &Vector::new(1.0, 1.0, 0.0),
+                                               &Vector::new(1.0, 0.0, 0.0), 3.0).unwrap();
+        assert!(equal_within_delta(-3.0, near));
+        assert!(equal_within_delta(3.0, far));
+    }
+
+    #[test]
+    fn occlusion_clear_path(){
+        let a = Vector::new(0.0, 0.0, -20.0);
+        let b = Vector::new(1000.0, 0.0, -20.0);
+        assert!(!is_occluded_by_sphere(&a, &b, &Vector::new(500.0, 60.0, -20.0), 50.0));
+        assert!(!is_occluded_by_sphere(&a, &b, &Vector::new(500.0, 0.0, 40.0), 50.0));
+    }
+
+    #[test]
+    fn occlusion_blocked_path(){
+        let a = Vector::new(0.0, 0.0, -20.0);
+        let b = Vector::new(1000.0, 0.0, -20.0);
+        let center = Vector::new(500.0, 30.0, -20.0);
+        assert!(is_occluded_by_sphere(&a, &b, &center, 50.0));
+        assert!(is_occluded_by_sphere(&b, &a, &center, 50.0));
+        // An endpoint inside the sphere blocks the sight as well.
+        assert!(is_occluded_by_sphere(&a, &Vector::new(500.0, 0.0, -20.0), &center, 50.0));
+        assert!(is_occluded_by_sphere(&center, &center, &center, 50.0));
+    }
+
+    #[test]
+    fn occlusion_sphere_behind_endpoint(){
+        let a = Vector::new(0.0, 0.0, -20.0);
+        let b = Vector::new(400.0, 0.0, -20.0);
+        let center = Vector::new(500.0, 0.0, -20.0);
+        assert!(!is_occluded_by_sphere(&a, &b, &center, 50.0));
+        assert!(!is_occluded_by_sphere(&b, &a, &center, 50.0));
+        assert!(!is_occluded_by_sphere(&a, &a, &center, 50.0));
+    }
+
+    #[test]
+    fn grazing_angle_vertical(){
+        assert_eq!(FRAC_PI_2, grazing_angle(&Vector::new(0.0, 0.0, -30.0)));
+    }
+
+    #[test]
+    fn grazing_angle_horizontal(){
+        assert_eq!(0.0, grazing_angle(&Vector::new(3.0, -4.0, 0.0)));
+        assert_eq!(0.0, grazing_angle(&Vector::get_world_origin()));
+    }
+
+    #[test]
+    fn grazing_angle_45_degrees(){
+        assert!(equal_within_delta(FRAC_PI_4, grazing_angle(&Vector::new(0.0, 2.0, -2.0))));
+        assert!(equal_within_delta(FRAC_PI_4, grazing_angle(&Vector::new(1.0, 1.0, -(2.0_f64).sqrt()))));
+        // An upgoing ray hits the surface at the same angle.
+        assert!(equal_within_delta(FRAC_PI_4, grazing_angle(&Vector::new(0.0, 2.0, 2.0))));
+    }
+}
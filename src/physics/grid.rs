@@ -0,0 +1,84 @@
+//! This module contains generators of regularly sampled points, e.g. for search coverage.
+
+use crate::physics::vectors::PolarVec;
+
+/// Returns the values of steps evenly spaced points from range.0 to range.1, both included. A single
+/// step returns range.0.
+pub(crate) fn linspace(range: (f64, f64), steps: usize) -> impl Iterator<Item = f64> {
+    let increment = if steps > 1 { (range.1 - range.0) / (steps - 1) as f64 } else { 0.0 };
+    (0..steps).map(move |i| range.0 + increment * i as f64)
+}
+
+/// Returns a grid of [PolarVec]s sampling a volume in spherical coordinates.
+///
+/// The radius is in m, the azimut phi and the polar angle theta are in radians as in
+/// [PolarVec::new]. steps.0 radii, steps.1 azimuths and steps.2 polar angles are evenly spaced
+/// over their ranges, including both ends, so a full circle of phi is sampled without duplicate
+/// by the range `(0, 2π - 2π / steps.1)`. A single step samples only the start of a range. The
+/// point of radius i, azimuth j and polar angle k is found at index
+/// `(i * steps.1 + j) * steps.2 + k`.
+///
+/// Every point is canonicalized by [PolarVec::new], so points on the z axis or at radius 0 are
+/// equal for all azimuths, but they are still returned once per azimuth.
+/// # Examples
+/// ```rust
+/// let grid = spherical_grid((100.0, 500.0), (0.0, PI), (FRAC_PI_4, FRAC_PI_2), (5, 3, 2));
+/// assert_eq!(30, grid.len());
+/// assert_eq!(PolarVec::new(500.0, PI, FRAC_PI_2), grid[29]);
+/// ```
+pub fn spherical_grid(r_range: (f64, f64), phi_range: (f64, f64), theta_range: (f64, f64),
+                      steps: (usize, usize, usize)) -> Vec<PolarVec> {
+    let (r_steps, phi_steps, theta_steps) = steps;
+    let mut grid = Vec::with_capacity(r_steps * phi_steps * theta_steps);
+    for r in linspace(r_range, r_steps) {
+        for phi in linspace(phi_range, phi_steps) {
+            grid.extend(linspace(theta_range, theta_steps).map(|theta| PolarVec::new(r, phi, theta)));
+        }
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::vectors::Vector;
+    use crate::utils::helper_functions::assert_vec_eq;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    #[test]
+    fn spherical_grid_length(){
+        for steps in [(1, 1, 1), (4, 8, 3), (10, 1, 7), (2, 36, 18)].iter() {
+            let grid = spherical_grid((50.0, 2000.0), (0.0, 1.5 * PI), (0.2, PI - 0.2), *steps);
+            assert_eq!(steps.0 * steps.1 * steps.2, grid.len());
+        }
+        assert!(spherical_grid((50.0, 2000.0), (0.0, PI), (0.2, 1.0), (3, 0, 4)).is_empty());
+    }
+
+    #[test]
+    fn spherical_grid_points(){
+        let grid = spherical_grid((100.0, 300.0), (0.0, PI), (FRAC_PI_4, FRAC_PI_2), (3, 3, 2));
+        assert_eq!(PolarVec::new(100.0, 0.0, FRAC_PI_4), grid[0]);
+        assert_eq!(PolarVec::new(100.0, 0.0, FRAC_PI_2), grid[1]);
+        assert_eq!(PolarVec::new(100.0, FRAC_PI_2, FRAC_PI_4), grid[2]);
+        assert_eq!(PolarVec::new(200.0, FRAC_PI_2, FRAC_PI_2), grid[(3 + 1) * 2 + 1]);
+        assert_eq!(PolarVec::new(300.0, PI, FRAC_PI_2), grid[17]);
+    }
+
+    #[test]
+    fn spherical_grid_canonicalized(){
+        // An azimuth of 2π wraps to 0 and the pole has no azimuth.
+        let grid = spherical_grid((10.0, 10.0), (0.0, 2.0 * PI), (0.0, FRAC_PI_2), (1, 2, 2));
+        assert_eq!(grid[0], grid[2]);
+        assert_eq!(grid[1], grid[3]);
+        assert_eq!(0.0, grid[2].get_phi_in_rad());
+    }
+
+    #[test]
+    fn spherical_grid_full_sphere(){
+        // Both ends of the polar range are included: the first sample points up, the last down.
+        let grid = spherical_grid((10.0, 10.0), (0.0, 1.5 * PI), (0.0, PI), (1, 4, 5));
+        assert_vec_eq(&Vector::new(0.0, 0.0, 10.0), &grid[0].to_vector());
+        assert_vec_eq(&Vector::new(0.0, 0.0, -10.0), &grid[4].to_vector());
+        assert_vec_eq(&Vector::new(0.0, 0.0, -10.0), &grid.last().unwrap().to_vector());
+    }
+}
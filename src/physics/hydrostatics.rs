@@ -0,0 +1,65 @@
+//! This module contains the hydrostatic relation between the depth of a sensor and the water
+//! pressure it measures.
+
+use crate::constants::G_ACC;
+
+/// The mean density of seawater in kg/m³.
+pub const SEAWATER_DENSITY: f64 = 1025.0;
+
+/// Returns the hydrostatic pressure in Pa at depth_m below the surface, calculated as
+/// `density * G_ACC * depth`. This is the gauge pressure, i.e. the increase over the pressure at
+/// the surface, so the atmospheric pressure has to be added for the absolute pressure. The
+/// density is assumed to be constant over the water column.
+/// # Examples
+/// ```rust
+/// let pressure = pressure_at_depth(100.0, SEAWATER_DENSITY);
+/// assert_eq!(1025.0 * G_ACC * 100.0, pressure);
+/// ```
+pub fn pressure_at_depth(depth_m: f64, water_density: f64) -> f64 {
+    water_density * G_ACC * depth_m
+}
+
+/// Returns the depth in m at which the hydrostatic gauge pressure reaches pressure_pa. This is
+/// the inverse of [pressure_at_depth], e.g. to derive the depth of a sensor from its pressure
+/// gauge.
+/// # Examples
+/// ```rust
+/// let depth = depth_from_pressure(pressure_at_depth(42.0, SEAWATER_DENSITY), SEAWATER_DENSITY);
+/// assert!((42.0 - depth).abs() < F64_DELTA);
+/// ```
+pub fn depth_from_pressure(pressure_pa: f64, water_density: f64) -> f64 {
+    pressure_pa / (water_density * G_ACC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::helper_functions::equal_within_delta;
+
+    /// The standard atmosphere in Pa.
+    const ATMOSPHERE: f64 = 101_325.0;
+
+    #[test]
+    fn one_atmosphere_per_ten_meters(){
+        let pressure = pressure_at_depth(10.0, SEAWATER_DENSITY);
+        assert!((pressure - ATMOSPHERE).abs() / ATMOSPHERE < 0.01);
+
+        let depth = depth_from_pressure(ATMOSPHERE, SEAWATER_DENSITY);
+        assert!((depth - 10.0).abs() < 0.1);
+
+        // The pressure grows linearly with depth.
+        assert!(equal_within_delta(100.0 * pressure, pressure_at_depth(1000.0, SEAWATER_DENSITY)));
+        assert_eq!(0.0, pressure_at_depth(0.0, SEAWATER_DENSITY));
+    }
+
+    #[test]
+    fn depth_pressure_round_trip(){
+        for depth in [0.0, 3.5, 120.0, 6000.0].iter() {
+            let pressure = pressure_at_depth(*depth, SEAWATER_DENSITY);
+            assert!(equal_within_delta(*depth, depth_from_pressure(pressure, SEAWATER_DENSITY)));
+        }
+        // Fresh water is lighter, so the same pressure is reached deeper.
+        let pressure = pressure_at_depth(50.0, SEAWATER_DENSITY);
+        assert!(depth_from_pressure(pressure, 1000.0) > 50.0);
+    }
+}
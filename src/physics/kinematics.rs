@@ -0,0 +1,469 @@
+//! This module contains functions describing the motion of platforms and contacts.
+
+use crate::constants::{F64_DELTA, G_ACC};
+use crate::physics::vectors::Vector;
+use std::f64::consts::TAU;
+
+/// Returns the speed over ground (the horizontal magnitude of the velocity in m/s) and the course
+/// over ground in rad for a velocity. The course is a bearing measured clockwise from north
+/// (the y axis) in range (0..2*pi]. The vertical velocity is ignored and a velocity without
+/// horizontal part has course 0.
+/// # Examples
+/// ```rust
+/// let (sog, cog) = velocity_to_sog_cog(&Vector::new(3.0, 0.0, -1.0));
+/// assert_eq!(3.0, sog);
+/// assert_eq!(FRAC_PI_2, cog);
+/// ```
+pub fn velocity_to_sog_cog(velocity: &Vector) -> (f64, f64) {
+    let sog = velocity.get_x().hypot(velocity.get_y());
+    if sog == 0.0 {
+        return (0.0, 0.0);
+    }
+    let cog = velocity.get_x().atan2(velocity.get_y()).rem_euclid(TAU);
+    (sog, cog)
+}
+
+/// Returns the vertical speed (the z component of the velocity in m/s, positive upwards) and the
+/// dive angle in rad for a velocity. The dive angle is measured below the horizontal, so a
+/// descending velocity has a positive angle up to pi/2 and a climbing one a negative angle.
+/// # Examples
+/// ```rust
+/// let (vertical_speed, dive_angle) = velocity_to_dive(&Vector::new(0.0, 0.0, -2.0));
+/// assert_eq!(-2.0, vertical_speed);
+/// assert_eq!(FRAC_PI_2, dive_angle);
+/// ```
+pub fn velocity_to_dive(velocity: &Vector) -> (f64, f64) {
+    let horizontal_speed = velocity.get_x().hypot(velocity.get_y());
+    let dive_angle = (-velocity.get_z()).atan2(horizontal_speed);
+    (velocity.get_z(), dive_angle)
+}
+
+/// Integrates a trajectory with the classic fourth order Runge-Kutta method and returns the
+/// positions at the start and after each of the steps, so the result holds steps + 1 positions.
+///
+/// The acceleration is given as a function of the time since the start (in s) and the current
+/// position. The integration starts at start with the velocity v0 and advances by dt seconds per
+/// step.
+/// # Examples
+/// ```rust
+/// let gravity = |_t: f64, _pos: &Vector| Vector::new(0.0, 0.0, -9.81);
+/// let positions = integrate_trajectory(&Vector::get_world_origin(), &Vector::new(1.0, 0.0, 0.0),
+///                                      gravity, 0.1, 10);
+/// assert_eq!(11, positions.len());
+/// ```
+pub fn integrate_trajectory(start: &Vector, v0: &Vector, accel: impl Fn(f64, &Vector) -> Vector,
+                            dt: f64, steps: usize) -> Vec<Vector> {
+    let mut positions = Vec::with_capacity(steps + 1);
+    let mut pos = *start;
+    let mut vel = *v0;
+    positions.push(pos);
+
+    for step in 0..steps {
+        let t = step as f64 * dt;
+        let half_dt = dt / 2.0;
+
+        let k1_pos = vel;
+        let k1_vel = accel(t, &pos);
+        let k2_pos = vel.add(&k1_vel.scale(half_dt));
+        let k2_vel = accel(t + half_dt, &pos.add(&k1_pos.scale(half_dt)));
+        let k3_pos = vel.add(&k2_vel.scale(half_dt));
+        let k3_vel = accel(t + half_dt, &pos.add(&k2_pos.scale(half_dt)));
+        let k4_pos = vel.add(&k3_vel.scale(dt));
+        let k4_vel = accel(t + dt, &pos.add(&k3_pos.scale(dt)));
+
+        let delta_pos = k1_pos.add(&k2_pos.scale(2.0)).add(&k3_pos.scale(2.0)).add(&k4_pos);
+        let delta_vel = k1_vel.add(&k2_vel.scale(2.0)).add(&k3_vel.scale(2.0)).add(&k4_vel);
+        pos = pos.add(&delta_pos.scale(dt / 6.0));
+        vel = vel.add(&delta_vel.scale(dt / 6.0));
+        positions.push(pos);
+    }
+    positions
+}
+
+/// Returns the earliest time t >= 0 at which a pursuer moving at pursuer_speed can meet a target
+/// moving with constant velocity, found by solving `|target + target_vel * t - pursuer| = speed * t`.
+fn solve_intercept_time(pursuer: &Vector, pursuer_speed: f64, target: &Vector, target_vel: &Vector) -> Option<f64> {
+    let offset = target.sub(pursuer);
+    let a = target_vel.dot(target_vel) - pursuer_speed * pursuer_speed;
+    let b = 2.0 * offset.dot(target_vel);
+    let c = offset.dot(&offset);
+    if c == 0.0 {
+        return Some(0.0);
+    }
+
+    if a.abs() < F64_DELTA {
+        // Pursuer and target are equally fast, only a target coming closer can be caught.
+        let t = -c / b;
+        return if b < 0.0 { Some(t) } else { None };
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    let t1 = (-b - root) / (2.0 * a);
+    let t2 = (-b + root) / (2.0 * a);
+    [t1.min(t2), t1.max(t2)].iter().copied().find(|t| *t >= 0.0)
+}
+
+/// Returns the unit direction in which a pursuer moving at pursuer_speed (in m/s) has to travel to
+/// intercept a target moving with the constant velocity target_vel, or None if the pursuer is too
+/// slow to ever catch the target. If pursuer and target share their position, the pursuer simply
+/// follows the target's course, which is None for a stationary target.
+/// # Examples
+/// ```rust
+/// let course = intercept_course(&Vector::get_world_origin(), 10.0, &Vector::new(100.0, 0.0, 0.0),
+///                               &Vector::new(0.0, 6.0, 0.0)).unwrap();
+/// assert!(equal_within_delta(0.8, course.get_x()));
+/// assert!(equal_within_delta(0.6, course.get_y()));
+/// ```
+pub fn intercept_course(pursuer: &Vector, pursuer_speed: f64, target: &Vector, target_vel: &Vector) -> Option<Vector> {
+    let t = solve_intercept_time(pursuer, pursuer_speed, target, target_vel)?;
+    if t == 0.0 {
+        return target_vel.normalize();
+    }
+    target.add(&target_vel.scale(t)).sub(pursuer).normalize()
+}
+
+/// Returns the time in s until a pursuer moving at pursuer_speed (in m/s) on the course returned
+/// by [intercept_course] meets a target moving with the constant velocity target_vel, or None if
+/// the interception is impossible.
+/// # Examples
+/// ```rust
+/// let t = time_to_intercept(&Vector::get_world_origin(), 10.0, &Vector::new(100.0, 0.0, 0.0),
+///                           &Vector::new(0.0, 6.0, 0.0));
+/// assert!(equal_within_delta(12.5, t.unwrap()));
+/// ```
+pub fn time_to_intercept(pursuer: &Vector, pursuer_speed: f64, target: &Vector, target_vel: &Vector) -> Option<f64> {
+    solve_intercept_time(pursuer, pursuer_speed, target, target_vel)
+}
+
+/// Returns the lateral acceleration commanded by proportional navigation, calculated as
+/// `N * (los_rate × closing_velocity)`.
+///
+/// los_rate is the rotation rate of the line of sight from pursuer to target in rad/s, given as a
+/// vector along the rotation axis. closing_velocity points along the line of sight with the
+/// closing speed as its length. A line of sight that does not rotate means the pursuer is already
+/// on a collision course and no acceleration is commanded.
+/// # Examples
+/// ```rust
+/// let command = pro_nav_command(&Vector::new(0.0, 0.0, 0.1), &Vector::new(200.0, 0.0, 0.0), 3.0);
+/// assert_eq!(Vector::new(0.0, 60.0, 0.0), command);
+/// ```
+pub fn pro_nav_command(los_rate: &Vector, closing_velocity: &Vector, nav_constant: f64) -> Vector {
+    los_rate.cross(closing_velocity).scale(nav_constant)
+}
+
+/// Returns the time in s until two points moving with constant velocities reach their closest
+/// point of approach (CPA) and their distance at that time.
+///
+/// The time minimizes the length of the relative position `p2 - p1 + (v2 - v1) * t`. Tracks that
+/// are already moving apart have their CPA now, so negative times are clamped to 0. Points with
+/// the same velocity keep their distance and also report a time of 0.
+/// # Examples
+/// ```rust
+/// let (t, distance) = closest_point_of_approach(&Vector::new(0.0, 0.0, 0.0), &Vector::new(5.0, 0.0, 0.0),
+///                                               &Vector::new(100.0, 10.0, 0.0), &Vector::new(-5.0, 0.0, 0.0));
+/// assert_eq!((10.0, 10.0), (t, distance));
+/// ```
+pub fn closest_point_of_approach(p1: &Vector, v1: &Vector, p2: &Vector, v2: &Vector) -> (f64, f64) {
+    let rel_pos = p2.sub(p1);
+    let rel_vel = v2.sub(v1);
+    let speed_squared = rel_vel.dot(&rel_vel);
+    let t = if speed_squared == 0.0 {
+        0.0
+    } else {
+        (-rel_pos.dot(&rel_vel) / speed_squared).max(0.0)
+    };
+    (t, rel_pos.add(&rel_vel.scale(t)).magnitude())
+}
+
+/// Returns the position a contact at point reaches after dt seconds at constant velocity (dead
+/// reckoning), calculated as `point + velocity * dt`. A negative dt goes back in time.
+/// # Examples
+/// ```rust
+/// let position = extrapolate(&Vector::new(0.0, 0.0, -10.0), &Vector::new(2.0, 1.0, 0.0), 5.0);
+/// assert_eq!(Vector::new(10.0, 5.0, -10.0), position);
+/// ```
+pub fn extrapolate(point: &Vector, velocity: &Vector, dt: f64) -> Vector {
+    point.add(&velocity.scale(dt))
+}
+
+/// Returns the speed error in m/s that grows into the given position error in m when a position
+/// is extrapolated over the integration time in s, calculated as `range_error / integration_time`.
+/// The longer a contact is dead reckoned, the more precisely its speed has to be known.
+/// # Examples
+/// ```rust
+/// assert_eq!(0.5, speed_error_for_range_error(30.0, 60.0));
+/// ```
+pub fn speed_error_for_range_error(range_error_m: f64, integration_time_s: f64) -> f64 {
+    range_error_m / integration_time_s
+}
+
+/// Returns the position of a body that starts at start with the given velocity after t seconds of
+/// free fall, e.g. a dropped sonobuoy, calculated as `start + velocity * t - 0.5 * G_ACC * t^2`
+/// on the z (up) axis. Drag is ignored, so the result only holds for short falls through air
+/// and not for the descent through water.
+/// # Examples
+/// ```rust
+/// let position = ballistic_position(&Vector::new(0.0, 0.0, 100.0), &Vector::new(10.0, 0.0, 0.0), 2.0);
+/// assert_eq!(Vector::new(20.0, 0.0, 100.0 - 2.0 * G_ACC), position);
+/// ```
+pub fn ballistic_position(start: &Vector, velocity: &Vector, t: f64) -> Vector {
+    let drop = Vector::new(0.0, 0.0, -0.5 * G_ACC * t * t);
+    extrapolate(start, velocity, t).add(&drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::helper_functions::equal_within_delta;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    #[test]
+    fn sog_cog_north(){
+        let (sog, cog) = velocity_to_sog_cog(&Vector::new(0.0, 5.0, 2.0));
+        assert_eq!(5.0, sog);
+        assert_eq!(0.0, cog);
+    }
+
+    #[test]
+    fn sog_cog_east(){
+        let (sog, cog) = velocity_to_sog_cog(&Vector::new(4.0, 0.0, 0.0));
+        assert_eq!(4.0, sog);
+        assert_eq!(FRAC_PI_2, cog);
+    }
+
+    #[test]
+    fn sog_cog_diagonal(){
+        let (sog, cog) = velocity_to_sog_cog(&Vector::new(3.0, 3.0, 0.0));
+        assert!(equal_within_delta(18.0_f64.sqrt(), sog));
+        assert!(equal_within_delta(FRAC_PI_4, cog));
+
+        let (sog, cog) = velocity_to_sog_cog(&Vector::new(-3.0, -3.0, 0.0));
+        assert!(equal_within_delta(18.0_f64.sqrt(), sog));
+        assert!(equal_within_delta(PI + FRAC_PI_4, cog));
+
+        let (_, cog) = velocity_to_sog_cog(&Vector::new(-1.0, 1.0, 0.0));
+        assert!(equal_within_delta(TAU - FRAC_PI_4, cog));
+    }
+
+    #[test]
+    fn sog_cog_vertical_only(){
+        assert_eq!((0.0, 0.0), velocity_to_sog_cog(&Vector::new(0.0, 0.0, -3.0)));
+    }
+
+    #[test]
+    fn dive_level(){
+        assert_eq!((0.0, 0.0), velocity_to_dive(&Vector::new(3.0, -4.0, 0.0)));
+    }
+
+    #[test]
+    fn dive_straight_down(){
+        assert_eq!((-5.0, FRAC_PI_2), velocity_to_dive(&Vector::new(0.0, 0.0, -5.0)));
+    }
+
+    #[test]
+    fn dive_45_degrees(){
+        let (vertical_speed, dive_angle) = velocity_to_dive(&Vector::new(1.0, 1.0, -(2.0_f64.sqrt())));
+        assert_eq!(-(2.0_f64.sqrt()), vertical_speed);
+        assert!(equal_within_delta(FRAC_PI_4, dive_angle));
+
+        let (_, dive_angle) = velocity_to_dive(&Vector::new(0.0, 2.0, 2.0));
+        assert!(equal_within_delta(-FRAC_PI_4, dive_angle));
+    }
+
+    #[test]
+    fn integrate_constant_acceleration(){
+        let start = Vector::new(10.0, -5.0, 0.0);
+        let v0 = Vector::new(2.0, 1.0, 20.0);
+        let a = Vector::new(0.5, 0.0, -9.81);
+        let dt = 0.05;
+        let positions = integrate_trajectory(&start, &v0, |_t, _pos| a, dt, 100);
+
+        assert_eq!(101, positions.len());
+        assert_eq!(start, positions[0]);
+        for (step, pos) in positions.iter().enumerate() {
+            let t = step as f64 * dt;
+            let expected = start.add(&v0.scale(t)).add(&a.scale(0.5 * t * t));
+            assert!(equal_within_delta(expected.get_x(), pos.get_x()));
+            assert!(equal_within_delta(expected.get_y(), pos.get_y()));
+            assert!(equal_within_delta(expected.get_z(), pos.get_z()));
+        }
+    }
+
+    #[test]
+    fn integrate_spring(){
+        // A harmonic oscillator with omega = 1 starting at x = 1 follows x = cos(t).
+        let positions = integrate_trajectory(&Vector::new(1.0, 0.0, 0.0), &Vector::get_world_origin(),
+                                             |_t, pos| pos.scale(-1.0), 0.01, 300);
+        let last = positions.last().unwrap();
+        assert!((last.get_x() - 3.0_f64.cos()).abs() < 0.000001);
+    }
+
+    #[test]
+    fn intercept_crossing_target(){
+        let pursuer = Vector::new(0.0, 0.0, -10.0);
+        let target = Vector::new(100.0, 0.0, -10.0);
+        let target_vel = Vector::new(0.0, 6.0, 0.0);
+        let course = intercept_course(&pursuer, 10.0, &target, &target_vel).unwrap();
+
+        // The target is met after 12.5 s at (100, 75): a 3-4-5 triangle.
+        assert!(equal_within_delta(0.8, course.get_x()));
+        assert!(equal_within_delta(0.6, course.get_y()));
+        assert!(equal_within_delta(0.0, course.get_z()));
+        assert!(equal_within_delta(1.0, course.magnitude()));
+    }
+
+    #[test]
+    fn intercept_too_slow(){
+        let pursuer = Vector::get_world_origin();
+        let target = Vector::new(100.0, 0.0, 0.0);
+        assert_eq!(None, intercept_course(&pursuer, 5.0, &target, &Vector::new(6.0, 0.0, 0.0)));
+        assert_eq!(None, intercept_course(&pursuer, 5.0, &target, &Vector::new(0.0, 6.0, 0.0)));
+        assert_eq!(None, intercept_course(&pursuer, 6.0, &target, &Vector::new(0.0, 6.0, 0.0)));
+    }
+
+    #[test]
+    fn intercept_slow_approaching_target(){
+        // A slower target can still be met when it comes towards the pursuer.
+        let course = intercept_course(&Vector::get_world_origin(), 5.0, &Vector::new(100.0, 0.0, 0.0),
+                                      &Vector::new(-6.0, 0.0, 0.0)).unwrap();
+        assert!(equal_within_delta(1.0, course.get_x()));
+    }
+
+    #[test]
+    fn time_to_intercept_meets_target(){
+        let pursuer = Vector::new(-20.0, 35.0, -40.0);
+        let target = Vector::new(300.0, -150.0, -60.0);
+        let target_vel = Vector::new(-3.0, 7.5, 0.5);
+        let speed = 12.0;
+
+        let t = time_to_intercept(&pursuer, speed, &target, &target_vel).unwrap();
+        let course = intercept_course(&pursuer, speed, &target, &target_vel).unwrap();
+        let pursuer_at_t = pursuer.add(&course.scale(speed * t));
+        let target_at_t = target.add(&target_vel.scale(t));
+        assert!(pursuer_at_t.sub(&target_at_t).magnitude() < 0.0001);
+
+        assert!(equal_within_delta(12.5, time_to_intercept(&Vector::get_world_origin(), 10.0,
+            &Vector::new(100.0, 0.0, 0.0), &Vector::new(0.0, 6.0, 0.0)).unwrap()));
+    }
+
+    #[test]
+    fn time_to_intercept_impossible(){
+        assert_eq!(None, time_to_intercept(&Vector::get_world_origin(), 5.0,
+                                           &Vector::new(100.0, 0.0, 0.0), &Vector::new(6.0, 0.0, 0.0)));
+        assert_eq!(Some(0.0), time_to_intercept(&Vector::get_world_origin(), 5.0,
+                                                &Vector::get_world_origin(), &Vector::new(6.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn pro_nav_collision_course(){
+        let command = pro_nav_command(&Vector::get_world_origin(), &Vector::new(150.0, 20.0, -5.0), 4.0);
+        assert_eq!(Vector::get_world_origin(), command);
+    }
+
+    #[test]
+    fn pro_nav_correcting(){
+        // The target drifts to the left (+y) of a line of sight along x, so the line of sight
+        // turns counterclockwise and the pursuer is steered to the left as well.
+        let closing_velocity = Vector::new(200.0, 0.0, 0.0);
+        let command = pro_nav_command(&Vector::new(0.0, 0.0, 0.05), &closing_velocity, 3.0);
+        assert!(equal_within_delta(30.0, command.get_y()));
+        assert!(equal_within_delta(0.0, command.get_x()));
+        assert_eq!(0.0, command.dot(&closing_velocity));
+
+        let command = pro_nav_command(&Vector::new(0.0, 0.0, -0.05), &closing_velocity, 3.0);
+        assert!(equal_within_delta(-30.0, command.get_y()));
+    }
+
+    #[test]
+    fn cpa_head_on(){
+        let (t, distance) = closest_point_of_approach(&Vector::new(0.0, 0.0, -10.0), &Vector::new(4.0, 0.0, 0.0),
+                                                      &Vector::new(100.0, 0.0, -10.0), &Vector::new(-6.0, 0.0, 0.0));
+        assert!(equal_within_delta(10.0, t));
+        assert!(equal_within_delta(0.0, distance));
+    }
+
+    #[test]
+    fn cpa_parallel(){
+        let (t, distance) = closest_point_of_approach(&Vector::new(0.0, 0.0, 0.0), &Vector::new(5.0, 5.0, 0.0),
+                                                      &Vector::new(30.0, 40.0, 0.0), &Vector::new(5.0, 5.0, 0.0));
+        assert_eq!(0.0, t);
+        assert_eq!(50.0, distance);
+    }
+
+    #[test]
+    fn cpa_crossing(){
+        // Own ship heads north, the contact crosses from the east heading west and passes ahead.
+        let (t, distance) = closest_point_of_approach(&Vector::new(0.0, 0.0, 0.0), &Vector::new(0.0, 5.0, 0.0),
+                                                      &Vector::new(100.0, 100.0, 0.0), &Vector::new(-10.0, 0.0, 0.0));
+        // Relative motion (-10, -5) from (100, 100): t = 1500 / 125 = 12 s, offset (-20, 40).
+        assert!(equal_within_delta(12.0, t));
+        assert!(equal_within_delta(2000.0_f64.sqrt(), distance));
+    }
+
+    #[test]
+    fn cpa_diverging(){
+        let (t, distance) = closest_point_of_approach(&Vector::new(0.0, 0.0, 0.0), &Vector::new(-5.0, 0.0, 0.0),
+                                                      &Vector::new(100.0, 0.0, 0.0), &Vector::new(5.0, 0.0, 0.0));
+        assert_eq!(0.0, t);
+        assert_eq!(100.0, distance);
+    }
+
+    #[test]
+    fn extrapolate_zero_dt(){
+        let point = Vector::new(120.0, -40.0, -15.0);
+        assert_eq!(point, extrapolate(&point, &Vector::new(3.0, 4.0, -0.5), 0.0));
+    }
+
+    #[test]
+    fn extrapolate_straight_line(){
+        let start = Vector::new(100.0, 200.0, -20.0);
+        let velocity = Vector::new(4.0, -3.0, -0.5);
+        let mut position = start;
+        for _ in 0..8 {
+            position = extrapolate(&position, &velocity, 1.0);
+        }
+        assert_eq!(Vector::new(132.0, 176.0, -24.0), position);
+        assert_eq!(position, extrapolate(&start, &velocity, 8.0));
+        assert_eq!(start, extrapolate(&position, &velocity, -8.0));
+    }
+
+    #[test]
+    fn speed_error_for_range_error_shrinks_with_time(){
+        let short = speed_error_for_range_error(100.0, 60.0);
+        let long = speed_error_for_range_error(100.0, 600.0);
+        assert!(long < short);
+        assert!(equal_within_delta(10.0 * long, short));
+
+        // The tolerable speed error accumulates to exactly the range error.
+        let position = extrapolate(&Vector::get_world_origin(), &Vector::new(long, 0.0, 0.0), 600.0);
+        assert!(equal_within_delta(100.0, position.magnitude()));
+    }
+
+    #[test]
+    fn ballistic_position_at_start(){
+        let start = Vector::new(-30.0, 50.0, 120.0);
+        assert_eq!(start, ballistic_position(&start, &Vector::new(60.0, 0.0, 5.0), 0.0));
+    }
+
+    #[test]
+    fn ballistic_position_pure_drop(){
+        // A body dropped from rest falls g * t² / 2.
+        let start = Vector::new(10.0, 20.0, 150.0);
+        let position = ballistic_position(&start, &Vector::get_world_origin(), 3.0);
+        assert_eq!(10.0, position.get_x());
+        assert_eq!(20.0, position.get_y());
+        assert!(equal_within_delta(150.0 - 44.145, position.get_z()));
+    }
+
+    #[test]
+    fn ballistic_position_with_launch_velocity(){
+        // Thrown upwards with g m/s, the body is back at its start height after 2 s.
+        let start = Vector::new(0.0, 0.0, 50.0);
+        let position = ballistic_position(&start, &Vector::new(5.0, 0.0, G_ACC), 2.0);
+        assert!(equal_within_delta(10.0, position.get_x()));
+        assert!(equal_within_delta(50.0, position.get_z()));
+    }
+}
@@ -0,0 +1,117 @@
+//! This module contains functions for localizing contacts from passive measurements.
+
+use crate::constants::F64_DELTA;
+use std::f64::consts::{PI, TAU};
+
+/// Returns the bearing rate in rad/s of a leg of (time in s, bearing in rad) samples as the slope
+/// of a least squares line. Jumps of the bearings across 0 or 2π are unwrapped first. Returns None
+/// if the leg has less than two distinct times.
+fn fitted_bearing_rate(bearings: &[(f64, f64)]) -> Option<f64> {
+    let mut unwrapped = Vec::with_capacity(bearings.len());
+    for (t, bearing) in bearings {
+        let bearing = match unwrapped.last() {
+            Some((_, previous)) => previous + (bearing - previous + PI).rem_euclid(TAU) - PI,
+            None => *bearing,
+        };
+        unwrapped.push((*t, bearing));
+    }
+
+    let count = unwrapped.len() as f64;
+    let mean_t = unwrapped.iter().map(|(t, _)| t).sum::<f64>() / count;
+    let mean_bearing = unwrapped.iter().map(|(_, bearing)| bearing).sum::<f64>() / count;
+    let covariance: f64 = unwrapped.iter().map(|(t, bearing)| (t - mean_t) * (bearing - mean_bearing)).sum();
+    let variance: f64 = unwrapped.iter().map(|(t, _)| (t - mean_t).powi(2)).sum();
+    if unwrapped.len() < 2 || variance == 0.0 {
+        return None;
+    }
+    Some(covariance / variance)
+}
+
+/// Returns the range in m to a contact estimated from bearings taken on two legs before and after
+/// an own ship maneuver (Ekelund ranging).
+///
+/// Both legs are lists of (time in s, bearing in rad) samples whose bearing rate is fitted by a
+/// least squares line. While the contact keeps its course and speed, only the own ship's speed
+/// across the line of sight changes the bearing rate, so the range is
+/// `own_speed_change / (rate_leg1 - rate_leg2)`. own_speed_change is the own ship's speed across
+/// the line of sight on leg 2 minus that on leg 1 in m/s, counted positive in the direction of
+/// increasing bearing. The estimate assumes that the line of sight barely turns during the
+/// maneuver.
+///
+/// Returns None if a leg has less than two distinct times, the bearing rates are too close to
+/// distinguish or the samples do not fit the speed change and yield no positive range.
+/// # Examples
+/// ```rust
+/// let leg1 = [(0.0, 1.0), (10.0, 1.01)];
+/// let leg2 = [(20.0, 1.02), (30.0, 1.01)];
+/// let range = ekelund_range(&leg1, &leg2, 2.0).unwrap();
+/// assert!((range - 1000.0).abs() < 1e-6);
+/// ```
+pub fn ekelund_range(bearings_leg1: &[(f64, f64)], bearings_leg2: &[(f64, f64)], own_speed_change: f64) -> Option<f64> {
+    let rate_difference = fitted_bearing_rate(bearings_leg1)? - fitted_bearing_rate(bearings_leg2)?;
+    if rate_difference.abs() < F64_DELTA {
+        return None;
+    }
+    let range = own_speed_change / rate_difference;
+    if range > 0.0 && range.is_finite() {
+        Some(range)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::vectors::Vector;
+
+    /// Returns the bearings (azimut counterclockwise from east) from the own ship to the contact
+    /// every 5 s over a leg of the given duration.
+    fn leg(own_start: &Vector, own_vel: &Vector, target_start: &Vector, target_vel: &Vector, t0: f64,
+           duration: f64) -> Vec<(f64, f64)> {
+        (0..=(duration / 5.0) as usize).map(|step| {
+            let dt = 5.0 * step as f64;
+            let own = own_start.add(&own_vel.scale(dt));
+            let target = target_start.add(&target_vel.scale(t0 + dt));
+            let offset = target.sub(&own);
+            (t0 + dt, offset.get_y().atan2(offset.get_x()))
+        }).collect()
+    }
+
+    #[test]
+    fn ekelund_range_known_geometry(){
+        // The contact starts 6000 m north and crawls east at 2 m/s. The own ship runs east at
+        // 6 m/s for two minutes and turns back west.
+        let target_start = Vector::new(0.0, 6000.0, -50.0);
+        let target_vel = Vector::new(2.0, 0.0, 0.0);
+        let east = Vector::new(6.0, 0.0, 0.0);
+        let west = Vector::new(-6.0, 0.0, 0.0);
+        let leg1 = leg(&Vector::get_world_origin(), &east, &target_start, &target_vel, 0.0, 120.0);
+        let turn_point = east.scale(120.0);
+        let leg2 = leg(&turn_point, &west, &target_start, &target_vel, 120.0, 120.0);
+
+        // With the contact to the north, increasing bearings lie to the west.
+        let range = ekelund_range(&leg1, &leg2, 12.0).unwrap();
+        let true_range = target_start.add(&target_vel.scale(120.0)).sub(&turn_point).magnitude();
+        assert!((range - true_range).abs() / true_range < 0.01, "{} != {}", range, true_range);
+    }
+
+    #[test]
+    fn ekelund_range_unwraps_bearings(){
+        // The bearings cross from just below 2π to just above 0.
+        let leg1 = [(0.0, TAU - 0.01), (10.0, 0.0), (20.0, 0.01)];
+        let leg2 = [(30.0, 0.02), (40.0, 0.01), (50.0, 0.0)];
+        let range = ekelund_range(&leg1, &leg2, 2.0).unwrap();
+        assert!((range - 1000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn ekelund_range_indistinguishable(){
+        let leg1 = [(0.0, 1.0), (10.0, 1.01)];
+        assert_eq!(None, ekelund_range(&leg1, &[(20.0, 1.02), (30.0, 1.03)], 10.0));
+        assert_eq!(None, ekelund_range(&leg1, &[(20.0, 1.02)], 10.0));
+        assert_eq!(None, ekelund_range(&[], &leg1, 10.0));
+        // A speed change that does not fit the turn of the bearing rate.
+        assert_eq!(None, ekelund_range(&leg1, &[(20.0, 1.02), (30.0, 1.01)], -10.0));
+    }
+}
@@ -0,0 +1,197 @@
+//! This module contains a 3x3 matrix used to describe rotations.
+
+use crate::physics::vectors::Vector;
+
+/// A 3x3 matrix of double (```f64```) values stored as three row vectors.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Matrix3 {
+    rows: [Vector; 3]
+}
+
+impl Matrix3 {
+    /// Generates a new matrix from its rows.
+    /// # Examples
+    /// ```rust
+    /// let m = Matrix3::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+    /// assert_eq!(6.0, m.get(1, 2));
+    /// ```
+    pub const fn new(rows: [[f64; 3]; 3]) -> Matrix3 {
+        Matrix3 {
+            rows: [
+                Vector::new(rows[0][0], rows[0][1], rows[0][2]),
+                Vector::new(rows[1][0], rows[1][1], rows[1][2]),
+                Vector::new(rows[2][0], rows[2][1], rows[2][2]),
+            ]
+        }
+    }
+    /// Returns the identity matrix.
+    pub const fn identity() -> Matrix3 {
+        Matrix3::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+    }
+    /// Returns the rotation matrix for the given Euler angles in rad, applied in the intrinsic
+    /// order Z-Y-X: first yaw around the z axis, then pitch around the new y axis and finally roll
+    /// around the new x axis. All rotations are counterclockwise when looking down the axis, so a
+    /// yaw of pi/2 turns the x axis onto the y axis.
+    /// # Examples
+    /// ```rust
+    /// let m = Matrix3::from_euler(FRAC_PI_2, 0.0, 0.0);
+    /// let vec = m.mul_vector(&Vector::new(1.0, 0.0, 0.0));
+    /// assert!((vec.get_y() - 1.0).abs() < F64_DELTA);
+    /// ```
+    pub fn from_euler(yaw: f64, pitch: f64, roll: f64) -> Matrix3 {
+        let (sy, cy) = yaw.sin_cos();
+        let (sp, cp) = pitch.sin_cos();
+        let (sr, cr) = roll.sin_cos();
+        Matrix3::new([
+            [cy * cp, cy * sp * sr - sy * cr, cy * sp * cr + sy * sr],
+            [sy * cp, sy * sp * sr + cy * cr, sy * sp * cr - cy * sr],
+            [-sp, cp * sr, cp * cr],
+        ])
+    }
+    /// Returns the value in the given row and column.
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        let row = &self.rows[row];
+        match col {
+            0 => row.get_x(),
+            1 => row.get_y(),
+            2 => row.get_z(),
+            _ => panic!("column index {} out of range for Matrix3", col),
+        }
+    }
+    /// Returns the given row as a vector.
+    pub fn get_row(&self, row: usize) -> &Vector {
+        &self.rows[row]
+    }
+    /// Returns the product of this matrix with a vector.
+    /// # Examples
+    /// ```rust
+    /// let m = Matrix3::new([[2.0, 0.0, 0.0], [0.0, 3.0, 0.0], [0.0, 0.0, 4.0]]);
+    /// assert_eq!(Vector::new(2.0, 3.0, 4.0), m.mul_vector(&Vector::new(1.0, 1.0, 1.0)));
+    /// ```
+    pub fn mul_vector(&self, vec: &Vector) -> Vector {
+        Vector::new(self.rows[0].dot(vec), self.rows[1].dot(vec), self.rows[2].dot(vec))
+    }
+    /// Returns the product of this matrix with another matrix (self * other).
+    pub fn mul_matrix(&self, other: &Matrix3) -> Matrix3 {
+        let columns = other.transpose();
+        let mut values = [[0.0; 3]; 3];
+        for (row, values_row) in values.iter_mut().enumerate() {
+            for (col, value) in values_row.iter_mut().enumerate() {
+                *value = self.rows[row].dot(&columns.rows[col]);
+            }
+        }
+        Matrix3::new(values)
+    }
+    /// Returns the transposed matrix. For a rotation matrix this is its inverse.
+    pub fn transpose(&self) -> Matrix3 {
+        let mut values = [[0.0; 3]; 3];
+        for (row, values_row) in values.iter_mut().enumerate() {
+            for (col, value) in values_row.iter_mut().enumerate() {
+                *value = self.get(col, row);
+            }
+        }
+        Matrix3::new(values)
+    }
+    /// Returns the inverse of the matrix, or None if the matrix is singular.
+    /// # Examples
+    /// ```rust
+    /// let m = Matrix3::new([[2.0, 0.0, 0.0], [0.0, 4.0, 0.0], [0.0, 0.0, 8.0]]);
+    /// let inverse = m.inverse().unwrap();
+    /// assert_eq!(Matrix3::identity(), m.mul_matrix(&inverse));
+    /// ```
+    pub fn inverse(&self) -> Option<Matrix3> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let m = |row: usize, col: usize| self.get(row, col);
+        let mut values = [[0.0; 3]; 3];
+        for (row, values_row) in values.iter_mut().enumerate() {
+            for (col, value) in values_row.iter_mut().enumerate() {
+                // The inverse is the transposed cofactor matrix divided by the determinant.
+                let (r1, r2) = ((col + 1) % 3, (col + 2) % 3);
+                let (c1, c2) = ((row + 1) % 3, (row + 2) % 3);
+                *value = (m(r1, c1) * m(r2, c2) - m(r1, c2) * m(r2, c1)) / det;
+            }
+        }
+        Some(Matrix3::new(values))
+    }
+    /// Returns the determinant of the matrix. Rotation matrices have a determinant of 1.
+    pub fn determinant(&self) -> f64 {
+        let [a, b, c] = self.rows;
+        a.get_x() * (b.get_y() * c.get_z() - b.get_z() * c.get_y())
+            - a.get_y() * (b.get_x() * c.get_z() - b.get_z() * c.get_x())
+            + a.get_z() * (b.get_x() * c.get_y() - b.get_y() * c.get_x())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::helper_functions::equal_within_delta;
+    use std::f64::consts::FRAC_PI_2;
+
+    fn assert_matrix_eq(expected: &Matrix3, actual: &Matrix3) {
+        for row in 0..3 {
+            for col in 0..3 {
+                assert!(equal_within_delta(expected.get(row, col), actual.get(row, col)),
+                        "{:?} != {:?}", expected, actual);
+            }
+        }
+    }
+
+    #[test]
+    fn identity(){
+        let vec = Vector::new(3.0, -5.0, 7.5);
+        assert_eq!(vec, Matrix3::identity().mul_vector(&vec));
+        assert_eq!(1.0, Matrix3::identity().determinant());
+    }
+
+    #[test]
+    fn yaw_90(){
+        let m = Matrix3::from_euler(FRAC_PI_2, 0.0, 0.0);
+        let vec = m.mul_vector(&Vector::new(1.0, 0.0, 0.0));
+        assert!(equal_within_delta(0.0, vec.get_x()));
+        assert!(equal_within_delta(1.0, vec.get_y()));
+        assert!(equal_within_delta(0.0, vec.get_z()));
+    }
+
+    #[test]
+    fn euler_order(){
+        let yaw = Matrix3::from_euler(0.3, 0.0, 0.0);
+        let pitch = Matrix3::from_euler(0.0, -0.2, 0.0);
+        let roll = Matrix3::from_euler(0.0, 0.0, 0.7);
+        let composed = yaw.mul_matrix(&pitch).mul_matrix(&roll);
+        assert_matrix_eq(&composed, &Matrix3::from_euler(0.3, -0.2, 0.7));
+    }
+
+    #[test]
+    fn transpose_is_inverse(){
+        let m = Matrix3::from_euler(0.4, -1.1, 2.3);
+        assert_matrix_eq(&Matrix3::identity(), &m.mul_matrix(&m.transpose()));
+        assert_matrix_eq(&Matrix3::identity(), &m.transpose().mul_matrix(&m));
+        assert!(equal_within_delta(1.0, m.determinant()));
+    }
+
+    #[test]
+    fn mul_matrix_and_determinant(){
+        let a = Matrix3::new([[1.0, 2.0, 3.0], [0.0, 1.0, 4.0], [5.0, 6.0, 0.0]]);
+        let b = Matrix3::new([[-24.0, 18.0, 5.0], [20.0, -15.0, -4.0], [-5.0, 4.0, 1.0]]);
+        assert_eq!(1.0, a.determinant());
+        assert_eq!(Matrix3::identity(), a.mul_matrix(&b));
+        assert_eq!(Matrix3::new([[1.0, 0.0, 5.0], [2.0, 1.0, 6.0], [3.0, 4.0, 0.0]]), a.transpose());
+    }
+
+    #[test]
+    fn inverse(){
+        let a = Matrix3::new([[1.0, 2.0, 3.0], [0.0, 1.0, 4.0], [5.0, 6.0, 0.0]]);
+        let b = Matrix3::new([[-24.0, 18.0, 5.0], [20.0, -15.0, -4.0], [-5.0, 4.0, 1.0]]);
+        assert_eq!(Some(b), a.inverse());
+
+        let m = Matrix3::from_euler(0.4, -1.1, 2.3);
+        assert_matrix_eq(&m.transpose(), &m.inverse().unwrap());
+
+        let singular = Matrix3::new([[1.0, 2.0, 3.0], [2.0, 4.0, 6.0], [0.0, 1.0, 0.0]]);
+        assert_eq!(None, singular.inverse());
+    }
+}
@@ -1,3 +1,22 @@
+#![allow(dead_code)]
 pub mod vectors;
 mod coordinate_system;
-mod vector_points;
\ No newline at end of file
+mod vector_points;
+mod acoustics;
+mod kinematics;
+mod matrix;
+mod stats;
+mod track;
+mod plane;
+mod sim;
+mod geometry;
+mod geodetic;
+mod sampling;
+mod beamforming;
+mod quaternion;
+mod localization;
+mod multistatic;
+mod hydrostatics;
+mod raytrace;
+mod grid;
+mod vector_field;
\ No newline at end of file
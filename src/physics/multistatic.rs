@@ -0,0 +1,170 @@
+//! This module contains functions for multistatic sonar, where transmitter and receiver are
+//! placed apart from each other.
+
+use crate::physics::vectors::Vector;
+
+/// Returns the bistatic range in m, the length of the path from the transmitter via the target to
+/// the receiver. All targets with the same bistatic range lie on an ellipsoid with the transmitter
+/// and the receiver as its foci. For a monostatic sonar the bistatic range is twice the range.
+/// # Examples
+/// ```rust
+/// let range = bistatic_range(&Vector::get_world_origin(), &Vector::new(30.0, 40.0, 0.0),
+///                            &Vector::new(60.0, 0.0, 0.0));
+/// assert_eq!(100.0, range);
+/// ```
+pub fn bistatic_range(tx: &Vector, target: &Vector, rx: &Vector) -> f64 {
+    target.sub(tx).magnitude() + rx.sub(target).magnitude()
+}
+
+/// Returns the time in s sound needs to travel the bistatic range in m at the given sound speed
+/// in m/s.
+/// # Examples
+/// ```rust
+/// assert_eq!(2.0, bistatic_range_to_time(3000.0, 1500.0));
+/// ```
+pub fn bistatic_range_to_time(range: f64, sound_speed: f64) -> f64 {
+    range / sound_speed
+}
+
+/// Returns the propagation paths from a source to a receiver between a flat sea surface at
+/// surface_z and a flat seabed at bottom_z with up to max_bounces reflections, found with the
+/// method of images. Every path is the list of its points: the source, the reflection points in
+/// the order they are hit and the receiver.
+///
+/// The paths are ordered by their number of reflections, starting with the direct path. Every
+/// number of reflections has two paths: the one that first goes up to the surface and the one
+/// that first goes down to the seabed, in that order. A path with n reflections is the straight
+/// line from the source to the image of the receiver after mirroring it n times at the
+/// alternating boundaries, so its length equals the distance to that image. Returns no paths if
+/// the seabed does not lie below the surface or the source or the receiver lie outside the water.
+/// # Examples
+/// ```rust
+/// let paths = image_source_paths(&Vector::new(0.0, 0.0, -10.0), &Vector::new(100.0, 0.0, -10.0),
+///                                0.0, -50.0, 1);
+/// assert_eq!(3, paths.len());
+/// assert_eq!(vec![Vector::new(0.0, 0.0, -10.0), Vector::new(50.0, 0.0, 0.0),
+///                 Vector::new(100.0, 0.0, -10.0)], paths[1]);
+/// ```
+pub fn image_source_paths(source: &Vector, receiver: &Vector, surface_z: f64, bottom_z: f64,
+                          max_bounces: usize) -> Vec<Vec<Vector>> {
+    let in_water = |point: &Vector| (bottom_z..=surface_z).contains(&point.get_z());
+    if bottom_z >= surface_z || !in_water(source) || !in_water(receiver) {
+        return Vec::new();
+    }
+
+    let mut paths = vec![vec![*source, *receiver]];
+    for bounces in 1..=max_bounces {
+        for first in [surface_z, bottom_z].iter().copied() {
+            let second = if first == surface_z { bottom_z } else { surface_z };
+            // The unfolded height of a real height z is sign * z + offset. Every reflection mirrors
+            // the water beyond the boundary, which flips the sign and moves the offset.
+            let mut sign = 1.0;
+            let mut offset = 0.0;
+            let mut unfolded_boundaries = Vec::with_capacity(bounces);
+            for bounce in 0..bounces {
+                let boundary = if bounce % 2 == 0 { first } else { second };
+                let unfolded = sign * boundary + offset;
+                unfolded_boundaries.push((boundary, unfolded));
+                sign = -sign;
+                offset = 2.0 * unfolded - offset;
+            }
+            let image_z = sign * receiver.get_z() + offset;
+
+            let mut path = Vec::with_capacity(bounces + 2);
+            path.push(*source);
+            for (boundary, unfolded) in unfolded_boundaries {
+                let fraction = (unfolded - source.get_z()) / (image_z - source.get_z());
+                let horizontal = source.add(&receiver.sub(source).scale(fraction));
+                path.push(Vector::new(horizontal.get_x(), horizontal.get_y(), boundary));
+            }
+            path.push(*receiver);
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::helper_functions::equal_within_delta;
+
+    #[test]
+    fn monostatic(){
+        let sonar = Vector::new(10.0, -20.0, -5.0);
+        let target = Vector::new(310.0, 380.0, -5.0);
+        assert_eq!(1000.0, bistatic_range(&sonar, &target, &sonar));
+        assert_eq!(1000.0 / 1500.0, bistatic_range_to_time(bistatic_range(&sonar, &target, &sonar), 1500.0));
+    }
+
+    #[test]
+    fn offset_geometry(){
+        let tx = Vector::new(-400.0, 0.0, -10.0);
+        let rx = Vector::new(400.0, 0.0, -10.0);
+        let target = Vector::new(0.0, 300.0, -10.0);
+        assert_eq!(1000.0, bistatic_range(&tx, &target, &rx));
+        assert_eq!(bistatic_range(&rx, &target, &tx), bistatic_range(&tx, &target, &rx));
+
+        // Another point on the same iso-range ellipse with semi-axes 500 m and 300 m.
+        let on_ellipse = Vector::new(500.0 * 0.6, 300.0 * 0.8, -10.0);
+        assert!(equal_within_delta(1000.0, bistatic_range(&tx, &on_ellipse, &rx)));
+        assert!(equal_within_delta(0.5, bistatic_range_to_time(750.0, 1500.0)));
+    }
+
+    fn path_length(path: &[Vector]) -> f64 {
+        path.windows(2).map(|pair| pair[1].sub(&pair[0]).magnitude()).sum()
+    }
+
+    #[test]
+    fn image_source_direct_path(){
+        let source = Vector::new(0.0, 0.0, -20.0);
+        let receiver = Vector::new(100.0, 0.0, -40.0);
+        let paths = image_source_paths(&source, &receiver, 0.0, -100.0, 0);
+        assert_eq!(vec![vec![source, receiver]], paths);
+    }
+
+    #[test]
+    fn image_source_one_bounce(){
+        let source = Vector::new(0.0, 0.0, -20.0);
+        let receiver = Vector::new(100.0, 0.0, -40.0);
+        let paths = image_source_paths(&source, &receiver, 0.0, -100.0, 1);
+        assert_eq!(3, paths.len());
+
+        // The surface image of the receiver lies at z = 40.
+        let surface = &paths[1];
+        assert_eq!(3, surface.len());
+        assert_eq!(0.0, surface[1].get_z());
+        assert!(equal_within_delta(100.0 / 3.0, surface[1].get_x()));
+        assert!(equal_within_delta(Vector::new(100.0, 0.0, 40.0).sub(&source).magnitude(), path_length(surface)));
+
+        // The seabed image of the receiver lies at z = -160.
+        let bottom = &paths[2];
+        assert_eq!(-100.0, bottom[1].get_z());
+        assert!(equal_within_delta(800.0 / 14.0, bottom[1].get_x()));
+        assert!(equal_within_delta(Vector::new(100.0, 0.0, -160.0).sub(&source).magnitude(), path_length(bottom)));
+    }
+
+    #[test]
+    fn image_source_multiple_bounces(){
+        let source = Vector::new(0.0, 0.0, -20.0);
+        let receiver = Vector::new(0.0, 100.0, -40.0);
+        let paths = image_source_paths(&source, &receiver, 0.0, -100.0, 3);
+        assert_eq!(7, paths.len());
+
+        // Surface, then seabed: the receiver image lies at z = 160.
+        let surface_bottom = &paths[3];
+        assert_eq!(vec![0.0, -100.0], surface_bottom[1..3].iter().map(|p| p.get_z()).collect::<Vec<f64>>());
+        assert!(equal_within_delta(Vector::new(0.0, 100.0, 160.0).sub(&source).magnitude(), path_length(surface_bottom)));
+        // Seabed, surface, seabed.
+        assert_eq!(vec![-100.0, 0.0, -100.0], paths[6][1..4].iter().map(|p| p.get_z()).collect::<Vec<f64>>());
+        // The reflection points advance steadily towards the receiver.
+        assert!(paths[6].windows(2).all(|pair| pair[1].get_y() > pair[0].get_y()));
+    }
+
+    #[test]
+    fn image_source_outside_water(){
+        let receiver = Vector::new(100.0, 0.0, -40.0);
+        assert!(image_source_paths(&Vector::new(0.0, 0.0, 5.0), &receiver, 0.0, -100.0, 2).is_empty());
+        assert!(image_source_paths(&Vector::new(0.0, 0.0, -20.0), &receiver, -100.0, 0.0, 2).is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+//! This module contains a plane used to model reflecting boundaries such as the sea surface and
+//! the seabed.
+
+use crate::constants::F64_DELTA;
+use crate::physics::vectors::Vector;
+
+/// An infinite plane given by a point on the plane and its unit normal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Plane {
+    point: Vector,
+    normal: Vector
+}
+
+impl Plane {
+    /// Generates a new plane through point with the given normal, which is normalized to length 1.
+    /// Returns None if the normal has no length.
+    /// # Examples
+    /// ```rust
+    /// let seabed = Plane::new(Vector::new(0.0, 0.0, -100.0), Vector::new(0.0, 0.0, 5.0)).unwrap();
+    /// assert_eq!(&Vector::new(0.0, 0.0, 1.0), seabed.get_normal());
+    /// ```
+    pub fn new(point: Vector, normal: Vector) -> Option<Plane> {
+        Some(Plane { point, normal: normal.normalize()? })
+    }
+    /// Returns the horizontal plane at height z with its normal pointing up.
+    pub fn horizontal(z: f64) -> Plane {
+        Plane { point: Vector::new(0.0, 0.0, z), normal: Vector::new(0.0, 0.0, 1.0) }
+    }
+    /// Returns the point the plane was defined through.
+    pub fn get_point(&self) -> &Vector {
+        &self.point
+    }
+    /// Returns the unit normal of the plane.
+    pub fn get_normal(&self) -> &Vector {
+        &self.normal
+    }
+    /// Returns the point where a ray starting at origin in direction dir hits the plane. Returns
+    /// None if the ray runs parallel to the plane or points away from it.
+    /// # Examples
+    /// ```rust
+    /// let seabed = Plane::horizontal(-100.0);
+    /// let hit = seabed.intersect_ray(&Vector::new(0.0, 0.0, -10.0), &Vector::new(1.0, 0.0, -1.0));
+    /// assert_eq!(Some(Vector::new(90.0, 0.0, -100.0)), hit);
+    /// ```
+    pub fn intersect_ray(&self, origin: &Vector, dir: &Vector) -> Option<Vector> {
+        let denominator = self.normal.dot(dir);
+        if denominator.abs() < F64_DELTA {
+            return None;
+        }
+        let t = self.normal.dot(&self.point.sub(origin)) / denominator;
+        if t < 0.0 {
+            return None;
+        }
+        Some(origin.add(&dir.scale(t)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creation(){
+        let plane = Plane::new(Vector::new(1.0, 2.0, -50.0), Vector::new(0.0, 0.0, -3.0)).unwrap();
+        assert_eq!(&Vector::new(1.0, 2.0, -50.0), plane.get_point());
+        assert_eq!(&Vector::new(0.0, 0.0, -1.0), plane.get_normal());
+        assert_eq!(None, Plane::new(Vector::get_world_origin(), Vector::get_world_origin()));
+    }
+
+    #[test]
+    fn downward_ray_hits_seabed(){
+        let seabed = Plane::horizontal(-100.0);
+        let hit = seabed.intersect_ray(&Vector::new(10.0, 20.0, -10.0), &Vector::new(0.0, 0.0, -1.0));
+        assert_eq!(Some(Vector::new(10.0, 20.0, -100.0)), hit);
+
+        let hit = seabed.intersect_ray(&Vector::new(0.0, 0.0, -10.0), &Vector::new(3.0, 4.0, -9.0));
+        assert_eq!(Some(Vector::new(30.0, 40.0, -100.0)), hit);
+    }
+
+    #[test]
+    fn parallel_ray_misses(){
+        let seabed = Plane::horizontal(-100.0);
+        assert_eq!(None, seabed.intersect_ray(&Vector::new(0.0, 0.0, -10.0), &Vector::new(1.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn ray_pointing_away_misses(){
+        let seabed = Plane::horizontal(-100.0);
+        assert_eq!(None, seabed.intersect_ray(&Vector::new(0.0, 0.0, -10.0), &Vector::new(0.0, 1.0, 1.0)));
+
+        let surface = Plane::new(Vector::get_world_origin(), Vector::new(0.0, 0.0, -1.0)).unwrap();
+        assert_eq!(Some(Vector::new(0.0, 10.0, 0.0)),
+                   surface.intersect_ray(&Vector::new(0.0, 0.0, -10.0), &Vector::new(0.0, 1.0, 1.0)));
+    }
+}
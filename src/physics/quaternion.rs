@@ -0,0 +1,117 @@
+//! This module contains a unit quaternion used to describe rotations.
+
+use crate::physics::matrix::Matrix3;
+use crate::physics::vectors::Vector;
+
+/// A quaternion `w + xi + yj + zk` of double (```f64```) values. Quaternions of length 1 describe
+/// rotations.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Quaternion {
+    w: f64,
+    x: f64,
+    y: f64,
+    z: f64
+}
+
+impl Quaternion {
+    /// Generates a new quaternion from its scalar part w and its vector part x, y, z.
+    pub const fn new(w: f64, x: f64, y: f64, z: f64) -> Quaternion {
+        Quaternion { w, x, y, z }
+    }
+    /// Returns the quaternion of the rotation that changes nothing.
+    pub const fn identity() -> Quaternion {
+        Quaternion::new(1.0, 0.0, 0.0, 0.0)
+    }
+    /// Returns the rotation by angle (in rad) counterclockwise around the axis, or None if the
+    /// axis has no length.
+    /// # Examples
+    /// ```rust
+    /// let q = Quaternion::from_axis_angle(&Vector::new(0.0, 0.0, 1.0), FRAC_PI_2).unwrap();
+    /// let vec = q.rotate_vector(&Vector::new(1.0, 0.0, 0.0));
+    /// assert!((vec.get_y() - 1.0).abs() < F64_DELTA);
+    /// ```
+    pub fn from_axis_angle(axis: &Vector, angle: f64) -> Option<Quaternion> {
+        let axis = axis.normalize()?;
+        let (sin, cos) = (angle / 2.0).sin_cos();
+        Some(Quaternion::new(cos, axis.get_x() * sin, axis.get_y() * sin, axis.get_z() * sin))
+    }
+    /// Returns the scalar part w.
+    pub fn get_w(&self) -> f64 {
+        self.w
+    }
+    /// Returns the vector part x, y, z.
+    pub fn get_vector(&self) -> Vector {
+        Vector::new(self.x, self.y, self.z)
+    }
+    /// Returns the length of the quaternion.
+    pub fn magnitude(&self) -> f64 {
+        (self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+    }
+    /// Returns the conjugate quaternion, which for a rotation is the inverse rotation.
+    pub fn conjugate(&self) -> Quaternion {
+        Quaternion::new(self.w, -self.x, -self.y, -self.z)
+    }
+    /// Returns the Hamilton product (self * other): the rotation that first applies other and
+    /// then self.
+    pub fn mul(&self, other: &Quaternion) -> Quaternion {
+        Quaternion::new(
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+        )
+    }
+    /// Returns the vector rotated by this unit quaternion.
+    pub fn rotate_vector(&self, vec: &Vector) -> Vector {
+        // v' = v + 2w(u × v) + 2u × (u × v) with u the vector part.
+        let u = self.get_vector();
+        let t = u.cross(vec).scale(2.0);
+        vec.add(&t.scale(self.w)).add(&u.cross(&t))
+    }
+    /// Returns the rotation matrix of this unit quaternion, e.g. to use it with
+    /// [GeneralCoordSystem::new_with_rotation](crate::physics::coordinate_system::GeneralCoordSystem::new_with_rotation).
+    pub fn to_matrix(self) -> Matrix3 {
+        let Quaternion { w, x, y, z } = self;
+        Matrix3::new([
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y)],
+            [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x)],
+            [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y)],
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::helper_functions::{assert_vec_eq, equal_within_delta};
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn rotate_vector(){
+        let yaw = Quaternion::from_axis_angle(&Vector::new(0.0, 0.0, 2.0), FRAC_PI_2).unwrap();
+        assert!(equal_within_delta(1.0, yaw.magnitude()));
+        assert_vec_eq(&Vector::new(0.0, 1.0, 0.0), &yaw.rotate_vector(&Vector::new(1.0, 0.0, 0.0)));
+        assert_vec_eq(&Vector::new(1.0, 0.0, 0.0), &yaw.conjugate().rotate_vector(&Vector::new(0.0, 1.0, 0.0)));
+        assert_eq!(Vector::new(1.0, 2.0, 3.0), Quaternion::identity().rotate_vector(&Vector::new(1.0, 2.0, 3.0)));
+        assert_eq!(None, Quaternion::from_axis_angle(&Vector::get_world_origin(), 1.0));
+    }
+
+    #[test]
+    fn mul_composes_rotations(){
+        let yaw = Quaternion::from_axis_angle(&Vector::new(0.0, 0.0, 1.0), 0.3).unwrap();
+        let roll = Quaternion::from_axis_angle(&Vector::new(1.0, 0.0, 0.0), -1.2).unwrap();
+        let vec = Vector::new(0.5, -2.0, 4.0);
+        assert_vec_eq(&yaw.rotate_vector(&roll.rotate_vector(&vec)), &yaw.mul(&roll).rotate_vector(&vec));
+    }
+
+    #[test]
+    fn to_matrix(){
+        let q = Quaternion::from_axis_angle(&Vector::new(1.0, -2.0, 0.5), 2.1).unwrap();
+        let vec = Vector::new(3.0, 1.0, -7.0);
+        assert_vec_eq(&q.rotate_vector(&vec), &q.to_matrix().mul_vector(&vec));
+
+        let yaw = Quaternion::from_axis_angle(&Vector::new(0.0, 0.0, 1.0), 0.7).unwrap();
+        let m = Matrix3::from_euler(0.7, 0.0, 0.0);
+        assert_vec_eq(&m.mul_vector(&vec), &yaw.to_matrix().mul_vector(&vec));
+    }
+}
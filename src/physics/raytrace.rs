@@ -0,0 +1,204 @@
+//! This module contains a sound speed profile of the water column and a ray tracer that follows
+//! a sound ray bending through it.
+
+use crate::constants::F64_DELTA;
+use crate::physics::acoustics::refract;
+use crate::physics::vectors::Vector;
+
+/// The sound speed in m/s over depth, given as (depth in m, positive downwards, sound speed)
+/// samples sorted by depth.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SoundSpeedProfile {
+    samples: Vec<(f64, f64)>
+}
+
+impl SoundSpeedProfile {
+    /// Generates a new profile from (depth, sound speed) samples in any order.
+    /// # Examples
+    /// ```rust
+    /// let profile = SoundSpeedProfile::new(vec![(100.0, 1490.0), (0.0, 1510.0)]);
+    /// assert_eq!(0.0, profile.get_samples()[0].0);
+    /// ```
+    pub fn new(mut samples: Vec<(f64, f64)>) -> SoundSpeedProfile {
+        samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+        SoundSpeedProfile { samples }
+    }
+    /// Returns the samples of the profile sorted by depth.
+    pub fn get_samples(&self) -> &[(f64, f64)] {
+        &self.samples
+    }
+    /// Returns the sound speed at depth_m, interpolated linearly between the samples around the
+    /// depth. Above the first and below the last sample the speed of that sample is kept. Returns
+    /// None if the profile has no samples.
+    /// # Examples
+    /// ```rust
+    /// let profile = SoundSpeedProfile::new(vec![(0.0, 1510.0), (100.0, 1490.0)]);
+    /// assert_eq!(Some(1500.0), profile.speed_at(50.0));
+    /// assert_eq!(Some(1490.0), profile.speed_at(4000.0));
+    /// ```
+    pub fn speed_at(&self, depth_m: f64) -> Option<f64> {
+        let first = self.samples.first()?;
+        let index = self.samples.partition_point(|(depth, _)| *depth <= depth_m);
+        if index == 0 {
+            return Some(first.1);
+        }
+        if index == self.samples.len() {
+            return self.samples.last().map(|(_, speed)| *speed);
+        }
+        let (d0, c0) = self.samples[index - 1];
+        let (d1, c1) = self.samples[index];
+        Some(c0 + (c1 - c0) * (depth_m - d0) / (d1 - d0))
+    }
+}
+
+/// Returns the points of a sound ray launched at start towards east (the x axis), traced in steps
+/// of step_m along the ray until it reaches the horizontal range max_range_m from start.
+///
+/// The launch angle in rad is measured from the horizontal plane, positive upwards like the
+/// elevation of [Vector::from_range_bearing_elevation]. After every step Snell's law is applied
+/// between the sound speeds at the old and the new depth (z = -depth), so the ray bends towards
+/// slower water and turns where it would be totally reflected. A horizontal ray, e.g. one
+/// launched at 0, is bent towards slower water by the local gradient. The sea surface and the
+/// bottom are not modeled. The first point is start, the last step is shortened to end exactly
+/// at max_range_m. A vertical launch never gains range, so it returns only start, as does an
+/// empty profile or a step without length.
+/// # Examples
+/// ```rust
+/// let profile = SoundSpeedProfile::new(vec![(0.0, 1500.0)]);
+/// let ray = trace_ray(&profile, &Vector::new(0.0, 0.0, -10.0), 0.0, 10.0, 100.0);
+/// assert_eq!(Some(&Vector::new(100.0, 0.0, -10.0)), ray.last());
+/// ```
+pub fn trace_ray(profile: &SoundSpeedProfile, start: &Vector, launch_angle_rad: f64, step_m: f64,
+                 max_range_m: f64) -> Vec<Vector> {
+    let mut ray = vec![*start];
+    let mut dir = Vector::new(launch_angle_rad.cos(), 0.0, launch_angle_rad.sin());
+    let mut speed = match profile.speed_at(-start.get_z()) {
+        Some(speed) => speed,
+        None => return ray,
+    };
+    if step_m <= 0.0 || dir.get_x() < F64_DELTA {
+        return ray;
+    }
+    let boundary_normal = Vector::unit_z();
+    let mut position = *start;
+    loop {
+        if dir.get_z().abs() < F64_DELTA {
+            // Snell's law keeps a horizontal ray horizontal, so at a turning point the ray is bent
+            // towards slower water by the angle it turns over one step on a circle of radius
+            // speed / gradient.
+            let depth = -position.get_z();
+            let below = profile.speed_at(depth + step_m).unwrap_or(speed);
+            let above = profile.speed_at(depth - step_m).unwrap_or(speed);
+            let turn = (below - above) / (2.0 * speed);
+            dir = Vector::new(turn.cos(), 0.0, turn.sin());
+        }
+        let remaining = max_range_m - (position.get_x() - start.get_x());
+        if remaining <= F64_DELTA {
+            return ray;
+        }
+        let step = step_m.min(remaining / dir.get_x());
+        position = position.add(&dir.scale(step));
+        ray.push(position);
+
+        let new_speed = profile.speed_at(-position.get_z()).unwrap_or(speed);
+        dir = match refract(&dir, &boundary_normal, speed, new_speed) {
+            Some(refracted) => refracted,
+            None => Vector::new(dir.get_x(), dir.get_y(), -dir.get_z()),
+        };
+        speed = new_speed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::helper_functions::equal_within_delta;
+
+    #[test]
+    fn profile_interpolation(){
+        let profile = SoundSpeedProfile::new(vec![(200.0, 1485.0), (0.0, 1520.0), (50.0, 1510.0)]);
+        assert_eq!(Some(1520.0), profile.speed_at(-5.0));
+        assert_eq!(Some(1520.0), profile.speed_at(0.0));
+        assert_eq!(Some(1515.0), profile.speed_at(25.0));
+        assert_eq!(Some(1510.0), profile.speed_at(50.0));
+        assert!(equal_within_delta(1497.5, profile.speed_at(125.0).unwrap()));
+        assert_eq!(Some(1485.0), profile.speed_at(1000.0));
+        assert_eq!(None, SoundSpeedProfile::default().speed_at(10.0));
+    }
+
+    #[test]
+    fn isovelocity_ray_is_straight(){
+        let profile = SoundSpeedProfile::new(vec![(0.0, 1500.0), (1000.0, 1500.0)]);
+        let start = Vector::new(0.0, 0.0, -50.0);
+        let angle = -10.0_f64.to_radians();
+        let ray = trace_ray(&profile, &start, angle, 25.0, 1000.0);
+
+        let last = ray.last().unwrap();
+        assert!(equal_within_delta(1000.0, last.get_x()));
+        for point in ray.iter() {
+            let expected_z = -50.0 + point.get_x() * angle.tan();
+            assert!(equal_within_delta(expected_z, point.get_z()));
+            assert_eq!(0.0, point.get_y());
+        }
+    }
+
+    #[test]
+    fn downward_refracting_ray_curves_down(){
+        // The sound speed decreases with depth, so a ray bends downwards.
+        let straight = SoundSpeedProfile::new(vec![(0.0, 1500.0)]);
+        let downward = SoundSpeedProfile::new(vec![(0.0, 1520.0), (500.0, 1480.0)]);
+        let start = Vector::new(0.0, 0.0, -20.0);
+        let angle = -2.0_f64.to_radians();
+
+        let straight_ray = trace_ray(&straight, &start, angle, 10.0, 2000.0);
+        let curved_ray = trace_ray(&downward, &start, angle, 10.0, 2000.0);
+        let straight_end = straight_ray.last().unwrap();
+        let curved_end = curved_ray.last().unwrap();
+        assert!(equal_within_delta(straight_end.get_x(), curved_end.get_x()));
+        assert!(curved_end.get_z() < straight_end.get_z() - 10.0);
+
+        // The ray steepens with every step.
+        let slopes: Vec<f64> = curved_ray.windows(2)
+            .map(|pair| (pair[1].get_z() - pair[0].get_z()) / (pair[1].get_x() - pair[0].get_x()))
+            .collect();
+        assert!(slopes.windows(2).all(|pair| pair[1] <= pair[0]));
+    }
+
+    #[test]
+    fn horizontal_ray_bends_towards_slower_water(){
+        // In a linear gradient g the ray follows a circle of radius c / g, so after 2000 m it has
+        // dropped by R - sqrt(R^2 - 2000^2) = 105.7 m with R = 1518.4 / 0.08.
+        let downward = SoundSpeedProfile::new(vec![(0.0, 1520.0), (500.0, 1480.0)]);
+        let start = Vector::new(0.0, 0.0, -20.0);
+        let ray = trace_ray(&downward, &start, 0.0, 10.0, 2000.0);
+        let end = ray.last().unwrap();
+        assert!(equal_within_delta(2000.0, end.get_x()));
+        assert!((-20.0 - 105.7 - end.get_z()).abs() < 3.0);
+
+        let upward = SoundSpeedProfile::new(vec![(0.0, 1480.0), (1000.0, 1540.0)]);
+        let start = Vector::new(0.0, 0.0, -300.0);
+        let ray = trace_ray(&upward, &start, 0.0, 10.0, 2000.0);
+        assert!(ray.last().unwrap().get_z() > -300.0);
+        assert!(ray.windows(2).all(|pair| pair[1].get_z() >= pair[0].get_z()));
+    }
+
+    #[test]
+    fn upward_refracting_ray_turns(){
+        // The sound speed increases with depth, so a downgoing ray turns back up.
+        let upward = SoundSpeedProfile::new(vec![(0.0, 1480.0), (1000.0, 1540.0)]);
+        let start = Vector::new(0.0, 0.0, -100.0);
+        let ray = trace_ray(&upward, &start, -5.0_f64.to_radians(), 10.0, 10000.0);
+        let deepest = ray.iter().map(|point| point.get_z()).fold(f64::INFINITY, f64::min);
+        assert!(deepest < -100.0);
+        assert!(ray.last().unwrap().get_z() > deepest);
+    }
+
+    #[test]
+    fn trace_ray_degenerate_input(){
+        let profile = SoundSpeedProfile::new(vec![(0.0, 1500.0)]);
+        let start = Vector::new(0.0, 0.0, -10.0);
+        assert_eq!(vec![start], trace_ray(&profile, &start, std::f64::consts::FRAC_PI_2, 10.0, 100.0));
+        assert_eq!(vec![start], trace_ray(&profile, &start, 0.0, 0.0, 100.0));
+        assert_eq!(vec![start], trace_ray(&SoundSpeedProfile::default(), &start, 0.0, 10.0, 100.0));
+    }
+}
@@ -0,0 +1,138 @@
+//! This module contains functions for sampling points and directions, e.g. for Monte Carlo
+//! simulations. The random samplers need the `rand` feature.
+
+use std::f64::consts::PI;
+#[cfg(feature = "rand")]
+use std::f64::consts::TAU;
+
+use crate::physics::vectors::Vector;
+
+/// Returns a random unit vector whose directions are uniformly distributed over the surface of
+/// the sphere.
+///
+/// The height z is drawn uniformly from -1..1 and the azimut uniformly from 0..2π. By Archimedes'
+/// hat-box theorem every band of equal height has the same area, so unlike uniformly drawn polar
+/// angles the samples do not cluster at the poles.
+/// # Examples
+/// ```rust
+/// let mut rng = rand::thread_rng();
+/// let direction = random_on_unit_sphere(&mut rng);
+/// assert!((direction.magnitude() - 1.0).abs() < 1e-9);
+/// ```
+#[cfg(feature = "rand")]
+pub fn random_on_unit_sphere(rng: &mut impl rand::Rng) -> Vector {
+    let z: f64 = rng.gen_range(-1.0..=1.0);
+    let phi: f64 = rng.gen_range(0.0..TAU);
+    let r_xy = (1.0 - z * z).max(0.0).sqrt();
+    Vector::new(r_xy * phi.cos(), r_xy * phi.sin(), z)
+}
+
+/// Returns a random point that is uniformly distributed within the ball of the given radius
+/// around the origin.
+///
+/// The direction is drawn with [random_on_unit_sphere] and the distance from the origin as
+/// `radius * u^(1/3)` for a uniform u in 0..1, since the volume within a distance grows with its
+/// cube.
+/// # Examples
+/// ```rust
+/// let mut rng = rand::thread_rng();
+/// let point = random_in_sphere(&mut rng, 50.0);
+/// assert!(point.magnitude() <= 50.0);
+/// ```
+#[cfg(feature = "rand")]
+pub fn random_in_sphere(rng: &mut impl rand::Rng, radius: f64) -> Vector {
+    let u: f64 = rng.gen_range(0.0..=1.0);
+    random_on_unit_sphere(rng).scale(radius * u.cbrt())
+}
+
+/// Returns n unit vectors that are approximately evenly spread over the sphere, e.g. as look
+/// directions of a beam pattern sweep.
+///
+/// The points lie on a spiral from the north to the south pole: point i has the height
+/// `1 - (2i + 1) / n`, which places every point in a band of equal area, and is turned by the
+/// golden angle `π(3 - √5)` against its predecessor. The result is deterministic.
+/// # Examples
+/// ```rust
+/// let directions = fibonacci_sphere(100);
+/// assert_eq!(100, directions.len());
+/// ```
+pub fn fibonacci_sphere(n: usize) -> Vec<Vector> {
+    let golden_angle = PI * (3.0 - 5.0_f64.sqrt());
+    (0..n).map(|i| {
+        let z = 1.0 - (2.0 * i as f64 + 1.0) / n as f64;
+        let r_xy = (1.0 - z * z).sqrt();
+        let phi = golden_angle * i as f64;
+        Vector::new(r_xy * phi.cos(), r_xy * phi.sin(), z)
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fibonacci_sphere_unit_vectors(){
+        let directions = fibonacci_sphere(200);
+        assert_eq!(200, directions.len());
+        assert!(directions.iter().all(|direction| (direction.magnitude() - 1.0).abs() < 1e-9));
+        assert!(fibonacci_sphere(0).is_empty());
+        assert_eq!(vec![Vector::new(1.0, 0.0, 0.0)], fibonacci_sphere(1));
+    }
+
+    #[test]
+    fn fibonacci_sphere_even_spacing(){
+        let directions = fibonacci_sphere(200);
+        let nearest: Vec<f64> = directions.iter().enumerate().map(|(i, a)| {
+            directions.iter().enumerate()
+                .filter(|(j, _)| i != *j)
+                .map(|(_, b)| a.sub(b).magnitude())
+                .fold(f64::INFINITY, f64::min)
+        }).collect();
+        let min = nearest.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = nearest.iter().copied().fold(0.0, f64::max);
+
+        // Every point covers about 4π / n of the sphere, so neighbours are about 0.25 apart.
+        assert!(min > 0.2);
+        assert!(max < 0.3);
+        assert!(min / max > 0.8);
+    }
+
+    #[cfg(feature = "rand")]
+    mod random {
+        use super::*;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        const SAMPLES: usize = 20_000;
+
+        #[test]
+        fn random_on_unit_sphere_is_uniform(){
+            let mut rng = StdRng::seed_from_u64(7);
+            let samples: Vec<Vector> = (0..SAMPLES).map(|_| random_on_unit_sphere(&mut rng)).collect();
+
+            assert!(samples.iter().all(|sample| (sample.magnitude() - 1.0).abs() < 1e-9));
+            let mean = samples.iter().fold(Vector::get_world_origin(), |sum, sample| sum.add(sample))
+                .scale(1.0 / SAMPLES as f64);
+            assert!(mean.magnitude() < 0.03);
+
+            // Uniform angles would put far more than the 1.3 % of the area within 9° of the poles.
+            let polar = samples.iter().filter(|sample| sample.get_z().abs() > 0.9877).count();
+            assert!((polar as f64 / SAMPLES as f64) < 0.02);
+        }
+
+        #[test]
+        fn random_in_sphere_is_uniform(){
+            let mut rng = StdRng::seed_from_u64(11);
+            let samples: Vec<Vector> = (0..SAMPLES).map(|_| random_in_sphere(&mut rng, 40.0)).collect();
+
+            assert!(samples.iter().all(|sample| sample.magnitude() <= 40.0));
+            let mean = samples.iter().fold(Vector::get_world_origin(), |sum, sample| sum.add(sample))
+                .scale(1.0 / SAMPLES as f64);
+            assert!(mean.magnitude() < 1.0);
+
+            // The inner ball of half the radius holds an eighth of the volume.
+            let inner = samples.iter().filter(|sample| sample.magnitude() <= 20.0).count();
+            assert!((inner as f64 / SAMPLES as f64 - 0.125).abs() < 0.01);
+        }
+    }
+}
@@ -0,0 +1,413 @@
+//! This module contains functions for simulating sonar scenarios and planning missions.
+
+use crate::physics::vectors::{PolarVec, Vector};
+
+/// Returns the bearing from a sensor to the position a target moving with constant velocity
+/// will have after lead_time seconds. The bearing is a [PolarVec] whose radius is the range to
+/// that position.
+/// # Examples
+/// ```rust
+/// let bearing = lead_bearing(&Vector::get_world_origin(), &Vector::new(100.0, 0.0, 0.0),
+///                            &Vector::new(0.0, 10.0, 0.0), 10.0);
+/// assert_eq!(PolarVec::new(200.0_f64.sqrt() * 10.0, FRAC_PI_4, FRAC_PI_2), bearing);
+/// ```
+pub fn lead_bearing(sensor: &Vector, target: &Vector, target_vel: &Vector, lead_time: f64) -> PolarVec {
+    target.add(&target_vel.scale(lead_time)).sub(sensor).to_polar_vector()
+}
+
+/// Returns the center of the grid cell (i, j) when the rectangle between area_min and area_max is
+/// divided into steps.0 cells along x and steps.1 cells along y at height z.
+fn grid_cell_center(area_min: &Vector, area_max: &Vector, z: f64, steps: (usize, usize), i: usize, j: usize) -> Vector {
+    let dx = (area_max.get_x() - area_min.get_x()) / steps.0 as f64;
+    let dy = (area_max.get_y() - area_min.get_y()) / steps.1 as f64;
+    Vector::new(area_min.get_x() + (i as f64 + 0.5) * dx, area_min.get_y() + (j as f64 + 0.5) * dy, z)
+}
+
+/// Returns true if the point lies within the maximal range of any of the (position, range) sensors.
+fn is_covered(sensors: &[(Vector, f64)], point: &Vector) -> bool {
+    sensors.iter().any(|(position, range)| position.sub(point).magnitude() <= *range)
+}
+
+/// Returns a coverage map of a patrol area for sensors given as (position, maximal range in m).
+///
+/// The rectangle between area_min and area_max (only their x and y values are used) is divided
+/// into steps.0 cells along x and steps.1 cells along y at height z. `map[i][j]` is true if the
+/// center of cell i along x and j along y lies within the range of any sensor.
+/// # Examples
+/// ```rust
+/// let sensors = [(Vector::new(5.0, 5.0, 0.0), 3.0)];
+/// let map = coverage_map(&sensors, &Vector::new(0.0, 0.0, 0.0), &Vector::new(10.0, 10.0, 0.0), 0.0, (10, 10));
+/// assert!(map[5][5]);
+/// assert!(!map[0][0]);
+/// ```
+pub fn coverage_map(sensors: &[(Vector, f64)], area_min: &Vector, area_max: &Vector, z: f64,
+                    steps: (usize, usize)) -> Vec<Vec<bool>> {
+    (0..steps.0).map(|i| {
+        (0..steps.1).map(|j| {
+            is_covered(sensors, &grid_cell_center(area_min, area_max, z, steps, i, j))
+        }).collect()
+    }).collect()
+}
+
+/// Returns the fraction in range 0..1 of the grid cells of a patrol area that are covered by the
+/// sensors, using the grid described at [coverage_map]. An area without cells returns 0.
+/// # Examples
+/// ```rust
+/// let sensors = [(Vector::new(5.0, 5.0, 0.0), 100.0)];
+/// let fraction = coverage_percentage(&sensors, &Vector::new(0.0, 0.0, 0.0), &Vector::new(10.0, 10.0, 0.0), 0.0, (10, 10));
+/// assert_eq!(1.0, fraction);
+/// ```
+pub fn coverage_percentage(sensors: &[(Vector, f64)], area_min: &Vector, area_max: &Vector, z: f64,
+                           steps: (usize, usize)) -> f64 {
+    let cells = steps.0 * steps.1;
+    if cells == 0 {
+        return 0.0;
+    }
+    let covered = coverage_map(sensors, area_min, area_max, z, steps).iter()
+        .flatten()
+        .filter(|covered| **covered)
+        .count();
+    covered as f64 / cells as f64
+}
+
+/// Returns count sensor positions at height z that greedily maximize the coverage of a patrol
+/// area, using the grid described at [coverage_map].
+///
+/// The centers of the grid cells serve as candidate positions. Each sensor is placed at the
+/// candidate that covers the most cells not yet covered by the sensors placed before, with ties
+/// going to the first candidate. Once the area is fully covered, further sensors add nothing and
+/// end up at the first candidate.
+/// # Examples
+/// ```rust
+/// let sensors = place_sensors_greedy(&Vector::new(0.0, 0.0, 0.0), &Vector::new(100.0, 100.0, 0.0),
+///                                    -10.0, 20.0, 3, (20, 20));
+/// assert_eq!(3, sensors.len());
+/// ```
+pub fn place_sensors_greedy(area_min: &Vector, area_max: &Vector, z: f64, sensor_range: f64, count: usize,
+                            grid_steps: (usize, usize)) -> Vec<Vector> {
+    let cells: Vec<Vector> = (0..grid_steps.0)
+        .flat_map(|i| (0..grid_steps.1).map(move |j| (i, j)))
+        .map(|(i, j)| grid_cell_center(area_min, area_max, z, grid_steps, i, j))
+        .collect();
+    let mut covered = vec![false; cells.len()];
+    let mut placements = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        let mut best: Option<(usize, usize)> = None;
+        for (candidate_index, candidate) in cells.iter().enumerate() {
+            let gain = cells.iter().zip(covered.iter())
+                .filter(|(cell, covered)| !**covered && candidate.sub(cell).magnitude() <= sensor_range)
+                .count();
+            if best.is_none_or(|(_, best_gain)| gain > best_gain) {
+                best = Some((candidate_index, gain));
+            }
+        }
+        let (candidate_index, _) = match best {
+            Some(best) => best,
+            None => break,
+        };
+        let position = cells[candidate_index];
+        for (cell, covered) in cells.iter().zip(covered.iter_mut()) {
+            if position.sub(cell).magnitude() <= sensor_range {
+                *covered = true;
+            }
+        }
+        placements.push(position);
+    }
+    placements
+}
+
+/// Returns the centers of the grid cells of a patrol area that lie outside the range of all
+/// sensors, using the grid described at [coverage_map]. The centers are ordered by cell i along
+/// x first and j along y second.
+/// # Examples
+/// ```rust
+/// let sensors = [(Vector::new(5.0, 5.0, 0.0), 100.0)];
+/// let gaps = blind_zones(&sensors, &Vector::new(0.0, 0.0, 0.0), &Vector::new(10.0, 10.0, 0.0), 0.0, (10, 10));
+/// assert!(gaps.is_empty());
+/// ```
+pub fn blind_zones(sensors: &[(Vector, f64)], area_min: &Vector, area_max: &Vector, z: f64,
+                   steps: (usize, usize)) -> Vec<Vector> {
+    (0..steps.0)
+        .flat_map(|i| (0..steps.1).map(move |j| (i, j)))
+        .map(|(i, j)| grid_cell_center(area_min, area_max, z, steps, i, j))
+        .filter(|center| !is_covered(sensors, center))
+        .collect()
+}
+
+/// Returns the probability in range 0..1 that at least one of the (position, maximal range in m)
+/// sensors detects a target at the given point.
+///
+/// The detection probability of a single sensor falls linearly from 1 at its position to 0 at its
+/// maximal range. The sensors detect independently, so the combined probability is
+/// `1 - Π(1 - p_i)`.
+/// # Examples
+/// ```rust
+/// let sensors = [(Vector::new(0.0, 0.0, 0.0), 100.0), (Vector::new(100.0, 0.0, 0.0), 100.0)];
+/// let probability = cumulative_detection_probability(&sensors, &Vector::new(50.0, 0.0, 0.0));
+/// assert!(equal_within_delta(0.75, probability));
+/// ```
+pub fn cumulative_detection_probability(sensors: &[(Vector, f64)], point: &Vector) -> f64 {
+    let miss_probability: f64 = sensors.iter()
+        .map(|(position, range)| {
+            let detection = if *range > 0.0 {
+                (1.0 - position.sub(point).magnitude() / range).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            1.0 - detection
+        })
+        .product();
+    1.0 - miss_probability
+}
+
+/// Returns a synthetic range line of an active sonar ping as power values, one per sample, e.g.
+/// as input for [cfar](crate::signal::cfar).
+///
+/// Sample i holds the power received i / sample_rate_hz seconds after the ping, up to the
+/// two-way travel time to max_range. Every sample carries noise whose power is exponentially
+/// distributed around the noise level. Every target given as (position, target strength in dB)
+/// within max_range adds its echo at the sample of its two-way travel time. The echo level is
+/// `SL - 2TL + TS` with the transmission loss TL of spherical spreading `20 * log10(range)`.
+/// Levels in dB are converted to powers as `10^(level / 10)`.
+/// # Examples
+/// ```rust
+/// let mut rng = rand::thread_rng();
+/// let line = simulate_ping(&Vector::get_world_origin(), &[(Vector::new(750.0, 0.0, 0.0), 10.0)],
+///                          1500.0, 1000.0, 1000.0, 220.0, 60.0, &mut rng);
+/// assert_eq!(1334, line.len());
+/// ```
+#[cfg(feature = "rand")]
+#[allow(clippy::too_many_arguments)]
+pub fn simulate_ping(sensor: &Vector, targets: &[(Vector, f64)], sound_speed_mps: f64, sample_rate_hz: f64,
+                     max_range: f64, source_level: f64, noise_level: f64, rng: &mut impl rand::Rng) -> Vec<f64> {
+    let samples_per_meter = 2.0 / sound_speed_mps * sample_rate_hz;
+    let sample_count = (max_range * samples_per_meter).floor() as usize + 1;
+    let noise_power = 10.0_f64.powf(noise_level / 10.0);
+    let mut line: Vec<f64> = (0..sample_count)
+        .map(|_| {
+            let u: f64 = rng.gen();
+            -(1.0 - u).ln() * noise_power
+        })
+        .collect();
+
+    for (position, target_strength) in targets {
+        let range = position.sub(sensor).magnitude();
+        if range > max_range || range == 0.0 {
+            continue;
+        }
+        let index = (range * samples_per_meter).round() as usize;
+        let echo_level = source_level - 2.0 * 20.0 * range.log10() + target_strength;
+        if let Some(sample) = line.get_mut(index) {
+            *sample += 10.0_f64.powf(echo_level / 10.0);
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::helper_functions::equal_within_delta;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    #[test]
+    fn lead_bearing_follows_target(){
+        let sensor = Vector::new(0.0, 0.0, -20.0);
+        let target = Vector::new(1000.0, 0.0, -20.0);
+        let target_vel = Vector::new(0.0, 10.0, 0.0);
+
+        let instantaneous = lead_bearing(&sensor, &target, &target_vel, 0.0);
+        let lead = lead_bearing(&sensor, &target, &target_vel, 100.0);
+        assert_eq!(PolarVec::new(1000.0, 0.0, FRAC_PI_2), instantaneous);
+        assert_eq!(PolarVec::new(2_000_000.0_f64.sqrt(), FRAC_PI_4, FRAC_PI_2), lead);
+
+        // The bearing turns by the angle the target moves across the line of sight.
+        let turn = instantaneous.get_angle_difference_phi(&lead);
+        assert!(equal_within_delta((1000.0_f64 / 1000.0).atan(), turn));
+    }
+
+    #[test]
+    fn lead_bearing_south_of_sensor(){
+        let sensor = Vector::new(0.0, 0.0, -20.0);
+        let target = Vector::new(1000.0, 0.0, -20.0);
+        let target_vel = Vector::new(0.0, -10.0, 0.0);
+
+        // After 100 s the target lies south-east of the sensor, the azimut stays in 0..2*pi.
+        let lead = lead_bearing(&sensor, &target, &target_vel, 100.0);
+        assert!(equal_within_delta(2.0 * PI - FRAC_PI_4, lead.get_phi_in_rad()));
+        assert_eq!(PolarVec::new(2_000_000.0_f64.sqrt(), 2.0 * PI - FRAC_PI_4, FRAC_PI_2), lead);
+
+        let due_south = lead_bearing(&sensor, &Vector::new(0.0, -500.0, -20.0), &target_vel, 0.0);
+        assert!(equal_within_delta(PI + FRAC_PI_2, due_south.get_phi_in_rad()));
+    }
+
+    #[test]
+    fn coverage_map_single_sensor(){
+        let sensor = Vector::new(50.0, 50.0, -10.0);
+        let sensors = [(sensor, 30.0)];
+        let area_min = Vector::new(0.0, 0.0, 0.0);
+        let area_max = Vector::new(100.0, 100.0, 0.0);
+        let map = coverage_map(&sensors, &area_min, &area_max, -10.0, (20, 20));
+
+        assert_eq!(20, map.len());
+        assert!(map.iter().all(|row| row.len() == 20));
+        for (i, row) in map.iter().enumerate() {
+            for (j, covered) in row.iter().enumerate() {
+                let center = Vector::new(2.5 + 5.0 * i as f64, 2.5 + 5.0 * j as f64, -10.0);
+                assert_eq!(center.sub(&sensor).magnitude() <= 30.0, *covered);
+            }
+        }
+        // The coverage is symmetric around the sensor and the corners are uncovered.
+        assert!(map[10][10] && map[9][9] && map[4][10] && map[10][4]);
+        assert!(!map[0][0] && !map[19][19] && !map[0][19] && !map[19][0]);
+        assert!(!map[3][10] && !map[10][16]);
+    }
+
+    #[test]
+    fn coverage_map_without_sensors(){
+        let map = coverage_map(&[], &Vector::new(0.0, 0.0, 0.0), &Vector::new(10.0, 10.0, 0.0), 0.0, (4, 3));
+        assert_eq!(vec![vec![false; 3]; 4], map);
+    }
+
+    #[test]
+    fn coverage_percentage_overlapping_sensors(){
+        let area_min = Vector::new(0.0, 0.0, 0.0);
+        let area_max = Vector::new(100.0, 100.0, 0.0);
+        let one = [(Vector::new(40.0, 50.0, 0.0), 25.0)];
+        let two = [(Vector::new(40.0, 50.0, 0.0), 25.0), (Vector::new(60.0, 50.0, 0.0), 25.0)];
+
+        let one_fraction = coverage_percentage(&one, &area_min, &area_max, 0.0, (50, 50));
+        let two_fraction = coverage_percentage(&two, &area_min, &area_max, 0.0, (50, 50));
+        assert!(one_fraction > 0.0);
+        assert!(two_fraction > one_fraction);
+        assert!(two_fraction < 2.0 * one_fraction);
+    }
+
+    #[test]
+    fn coverage_percentage_full(){
+        let area_min = Vector::new(0.0, 0.0, 0.0);
+        let area_max = Vector::new(100.0, 100.0, 0.0);
+        let sensors = [(Vector::new(50.0, 50.0, 0.0), 75.0)];
+        assert!(equal_within_delta(1.0, coverage_percentage(&sensors, &area_min, &area_max, 0.0, (50, 50))));
+        assert_eq!(0.0, coverage_percentage(&[], &area_min, &area_max, 0.0, (50, 50)));
+        assert_eq!(0.0, coverage_percentage(&sensors, &area_min, &area_max, 0.0, (0, 50)));
+    }
+
+    #[test]
+    fn place_sensors_greedy_spreads_out(){
+        let area_min = Vector::new(0.0, 0.0, 0.0);
+        let area_max = Vector::new(100.0, 50.0, 0.0);
+        let one = place_sensors_greedy(&area_min, &area_max, -10.0, 20.0, 1, (20, 10));
+        let two = place_sensors_greedy(&area_min, &area_max, -10.0, 20.0, 2, (20, 10));
+        assert_eq!(1, one.len());
+        assert_eq!(2, two.len());
+        assert_eq!(one[0], two[0]);
+
+        let sensors_one: Vec<(Vector, f64)> = one.iter().map(|pos| (*pos, 20.0)).collect();
+        let sensors_two: Vec<(Vector, f64)> = two.iter().map(|pos| (*pos, 20.0)).collect();
+        let coverage_one = coverage_percentage(&sensors_one, &area_min, &area_max, -10.0, (20, 10));
+        let coverage_two = coverage_percentage(&sensors_two, &area_min, &area_max, -10.0, (20, 10));
+        assert!(coverage_two > coverage_one);
+
+        // The second sensor is placed far enough away to barely overlap with the first one.
+        assert!(two[0].sub(&two[1]).magnitude() > 30.0);
+        assert_eq!(-10.0, two[1].get_z());
+    }
+
+    #[test]
+    fn place_sensors_greedy_empty_grid(){
+        let sensors = place_sensors_greedy(&Vector::new(0.0, 0.0, 0.0), &Vector::new(10.0, 10.0, 0.0),
+                                           0.0, 5.0, 3, (0, 0));
+        assert!(sensors.is_empty());
+    }
+
+    #[test]
+    fn blind_zones_between_sensors(){
+        let area_min = Vector::new(0.0, 0.0, 0.0);
+        let area_max = Vector::new(100.0, 10.0, 0.0);
+        // Both sensors cover 40 m around them, leaving the strip between x = 40 and x = 60 open.
+        let sensors = [(Vector::new(0.0, 5.0, -5.0), 40.0), (Vector::new(100.0, 5.0, -5.0), 40.0)];
+        let gaps = blind_zones(&sensors, &area_min, &area_max, -5.0, (10, 1));
+
+        assert_eq!(vec![Vector::new(45.0, 5.0, -5.0), Vector::new(55.0, 5.0, -5.0)], gaps);
+
+        let map = coverage_map(&sensors, &area_min, &area_max, -5.0, (10, 1));
+        let uncovered = map.iter().flatten().filter(|covered| !**covered).count();
+        assert_eq!(uncovered, gaps.len());
+    }
+
+    #[test]
+    fn blind_zones_without_sensors(){
+        let gaps = blind_zones(&[], &Vector::new(0.0, 0.0, 0.0), &Vector::new(10.0, 10.0, 0.0), 0.0, (2, 2));
+        assert_eq!(4, gaps.len());
+        assert_eq!(Vector::new(2.5, 2.5, 0.0), gaps[0]);
+        assert_eq!(Vector::new(2.5, 7.5, 0.0), gaps[1]);
+    }
+
+    #[test]
+    fn cumulative_detection_probability_overlap(){
+        let a = (Vector::new(0.0, 0.0, -10.0), 100.0);
+        let b = (Vector::new(60.0, 0.0, -10.0), 100.0);
+        let point = Vector::new(20.0, 0.0, -10.0);
+
+        let only_a = cumulative_detection_probability(&[a], &point);
+        let only_b = cumulative_detection_probability(&[b], &point);
+        let both = cumulative_detection_probability(&[a, b], &point);
+        assert!(equal_within_delta(0.8, only_a));
+        assert!(equal_within_delta(0.6, only_b));
+        assert!(equal_within_delta(1.0 - 0.2 * 0.4, both));
+        assert!(both > only_a && both > only_b);
+    }
+
+    #[test]
+    fn cumulative_detection_probability_limits(){
+        let sensor = (Vector::new(0.0, 0.0, 0.0), 50.0);
+        assert_eq!(1.0, cumulative_detection_probability(&[sensor], &Vector::new(0.0, 0.0, 0.0)));
+        assert_eq!(0.0, cumulative_detection_probability(&[sensor], &Vector::new(50.0, 0.0, 0.0)));
+        assert_eq!(0.0, cumulative_detection_probability(&[sensor], &Vector::new(0.0, 80.0, 0.0)));
+        assert_eq!(0.0, cumulative_detection_probability(&[], &Vector::new(0.0, 0.0, 0.0)));
+    }
+
+    #[cfg(feature = "rand")]
+    mod random {
+        use super::*;
+        use crate::signal::cfar;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        #[test]
+        fn simulate_ping_echo_positions(){
+            let sensor = Vector::new(0.0, 0.0, -20.0);
+            let targets = [
+                (Vector::new(750.0, 0.0, -20.0), 10.0),
+                (Vector::new(0.0, -1200.0, -20.0), 0.0),
+                (Vector::new(3000.0, 0.0, -20.0), 20.0),
+            ];
+            let mut rng = StdRng::seed_from_u64(42);
+            let line = simulate_ping(&sensor, &targets, 1500.0, 1000.0, 2000.0, 220.0, 60.0, &mut rng);
+
+            // 2000 m take 2.67 s to travel there and back.
+            assert_eq!(2667, line.len());
+            assert!(line.iter().all(|power| *power >= 0.0));
+
+            // The target beyond max_range leaves no echo.
+            let detections: Vec<usize> = cfar(&line, 2, 16, 100.0).iter()
+                .enumerate()
+                .filter(|(_, detected)| **detected)
+                .map(|(index, _)| index)
+                .collect();
+            assert_eq!(vec![1000, 1600], detections);
+            assert!(line[1000] > line[1600]);
+        }
+
+        #[test]
+        fn simulate_ping_noise_only(){
+            let mut rng = StdRng::seed_from_u64(3);
+            let line = simulate_ping(&Vector::get_world_origin(), &[], 1500.0, 2000.0, 3000.0, 220.0, 50.0, &mut rng);
+            assert_eq!(8001, line.len());
+            let mean = line.iter().sum::<f64>() / line.len() as f64;
+            assert!((mean / 1e5 - 1.0).abs() < 0.05);
+        }
+    }
+}
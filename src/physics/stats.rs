@@ -0,0 +1,54 @@
+//! This module contains statistical helper functions used for tracking.
+
+use crate::physics::matrix::Matrix3;
+use crate::physics::vectors::Vector;
+
+/// Returns the Mahalanobis distance between a measurement and a predicted position, calculated as
+/// `sqrt(dᵀ * covariance⁻¹ * d)` with d being the difference between both. An offset along an axis
+/// with a small variance weighs more than the same offset along an axis with a large variance.
+/// Returns None if the covariance matrix is singular.
+/// # Examples
+/// ```rust
+/// let covariance = Matrix3::new([[4.0, 0.0, 0.0], [0.0, 4.0, 0.0], [0.0, 0.0, 4.0]]);
+/// let distance = mahalanobis_distance(&Vector::new(2.0, 0.0, 0.0), &Vector::get_world_origin(), &covariance);
+/// assert_eq!(Some(1.0), distance);
+/// ```
+pub fn mahalanobis_distance(measurement: &Vector, predicted: &Vector, covariance: &Matrix3) -> Option<f64> {
+    let inverse = covariance.inverse()?;
+    let diff = measurement.sub(predicted);
+    Some(diff.dot(&inverse.mul_vector(&diff)).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::helper_functions::equal_within_delta;
+
+    #[test]
+    fn mahalanobis_weights_variance(){
+        let covariance = Matrix3::new([[100.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 25.0]]);
+        let predicted = Vector::new(10.0, 10.0, -50.0);
+
+        let along_high_variance = mahalanobis_distance(&Vector::new(15.0, 10.0, -50.0), &predicted,
+                                                       &covariance).unwrap();
+        let along_low_variance = mahalanobis_distance(&Vector::new(10.0, 15.0, -50.0), &predicted,
+                                                      &covariance).unwrap();
+        assert!(equal_within_delta(0.5, along_high_variance));
+        assert!(equal_within_delta(5.0, along_low_variance));
+        assert!(along_low_variance > along_high_variance);
+    }
+
+    #[test]
+    fn mahalanobis_identity_is_euclidean(){
+        let distance = mahalanobis_distance(&Vector::new(3.0, 4.0, 12.0), &Vector::get_world_origin(),
+                                            &Matrix3::identity());
+        assert_eq!(Some(13.0), distance);
+    }
+
+    #[test]
+    fn mahalanobis_singular(){
+        let covariance = Matrix3::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 0.0]]);
+        assert_eq!(None, mahalanobis_distance(&Vector::new(1.0, 0.0, 0.0), &Vector::get_world_origin(),
+                                              &covariance));
+    }
+}
@@ -0,0 +1,535 @@
+//! This module contains the Track of a contact and functions to maintain tracks.
+
+use crate::physics::coordinate_system::CoordinateSystem;
+use crate::physics::vector_points::VectorPoint;
+use crate::physics::vectors::Vector;
+
+/// A track of a contact as a series of (timestamp in s, position) samples sorted by time.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Track {
+    points: Vec<(f64, Vector)>
+}
+
+impl Track {
+    /// Generates a new, empty track.
+    pub fn new() -> Track {
+        Track { points: Vec::new() }
+    }
+    /// Adds a sample to the track, keeping the samples sorted by time. A sample with the same
+    /// timestamp as an existing one is placed after it.
+    /// # Examples
+    /// ```rust
+    /// let mut track = Track::new();
+    /// track.push(2.0, Vector::new(2.0, 0.0, 0.0));
+    /// track.push(1.0, Vector::new(1.0, 0.0, 0.0));
+    /// assert_eq!(1.0, track.get_points()[0].0);
+    /// ```
+    pub fn push(&mut self, t: f64, position: Vector) {
+        let index = self.points.partition_point(|(time, _)| *time <= t);
+        self.points.insert(index, (t, position));
+    }
+    /// Returns the samples of the track sorted by time.
+    pub fn get_points(&self) -> &[(f64, Vector)] {
+        &self.points
+    }
+    /// Returns the latest sample of the track, or None if the track is empty.
+    pub fn latest(&self) -> Option<&(f64, Vector)> {
+        self.points.last()
+    }
+    /// Returns the number of samples in the track.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+    /// Returns true if the track has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+    /// Returns the two samples used to interpolate or extrapolate at time t: the samples around t,
+    /// or the first or last two samples if t lies before or after the track. Returns None if the
+    /// track has less than two samples.
+    fn bracketing_samples(&self, t: f64) -> Option<[(f64, Vector); 2]> {
+        if self.points.len() < 2 {
+            return None;
+        }
+        let index = self.points.partition_point(|(time, _)| *time <= t)
+            .clamp(1, self.points.len() - 1);
+        Some([self.points[index - 1], self.points[index]])
+    }
+    /// Returns the position at time t (in s), interpolated linearly between the samples around t.
+    /// Before the first and after the last sample the position is extrapolated from the first or
+    /// last two samples. A track with a single sample stays at that position and an empty track
+    /// returns None.
+    /// # Examples
+    /// ```rust
+    /// let mut track = Track::new();
+    /// track.push(0.0, Vector::new(0.0, 0.0, 0.0));
+    /// track.push(10.0, Vector::new(100.0, 0.0, 0.0));
+    /// assert_eq!(Some(Vector::new(25.0, 0.0, 0.0)), track.position_at(2.5));
+    /// ```
+    pub fn position_at(&self, t: f64) -> Option<Vector> {
+        let [(t0, p0), (t1, p1)] = match self.bracketing_samples(t) {
+            Some(samples) => samples,
+            None => return self.points.first().map(|(_, position)| *position),
+        };
+        if t1 <= t0 {
+            return Some(p1);
+        }
+        let fraction = (t - t0) / (t1 - t0);
+        Some(p0.add(&p1.sub(&p0).scale(fraction)))
+    }
+    /// Returns the velocity in m/s at time t (in s) as the finite difference between the samples
+    /// used by [Track::position_at]. Returns None if the track has less than two samples or these
+    /// samples share their timestamp.
+    /// # Examples
+    /// ```rust
+    /// let mut track = Track::new();
+    /// track.push(0.0, Vector::new(0.0, 0.0, 0.0));
+    /// track.push(10.0, Vector::new(100.0, 0.0, 0.0));
+    /// assert_eq!(Some(Vector::new(10.0, 0.0, 0.0)), track.velocity_at(5.0));
+    /// ```
+    pub fn velocity_at(&self, t: f64) -> Option<Vector> {
+        let [(t0, p0), (t1, p1)] = self.bracketing_samples(t)?;
+        if t1 <= t0 {
+            return None;
+        }
+        Some(p1.sub(&p0).scale(1.0 / (t1 - t0)))
+    }
+    /// Returns the track of this contact relative to a reference contact, e.g. the own ship for a
+    /// relative motion display.
+    ///
+    /// The relative track covers the time span in which both tracks have samples. It holds a
+    /// sample at every timestamp of either track within that span, with both positions taken from
+    /// [Track::position_at]. Returns None if one of the tracks is empty or their time spans do not
+    /// overlap.
+    /// # Examples
+    /// ```rust
+    /// let mut target = Track::new();
+    /// target.push(0.0, Vector::new(100.0, 0.0, 0.0));
+    /// target.push(10.0, Vector::new(100.0, 50.0, 0.0));
+    /// let mut own_ship = Track::new();
+    /// own_ship.push(0.0, Vector::new(0.0, 0.0, 0.0));
+    /// own_ship.push(10.0, Vector::new(0.0, 50.0, 0.0));
+    /// let relative = target.relative_to(&own_ship).unwrap();
+    /// assert_eq!(Some(Vector::new(100.0, 0.0, 0.0)), relative.position_at(5.0));
+    /// ```
+    pub fn relative_to(&self, reference: &Track) -> Option<Track> {
+        let start = self.points.first()?.0.max(reference.points.first()?.0);
+        let end = self.points.last()?.0.min(reference.points.last()?.0);
+        if start > end {
+            return None;
+        }
+        let mut times: Vec<f64> = self.points.iter().chain(reference.points.iter())
+            .map(|(t, _)| *t)
+            .filter(|t| (start..=end).contains(t))
+            .collect();
+        times.sort_by(|a, b| a.total_cmp(b));
+        times.dedup();
+
+        let points = times.into_iter()
+            .map(|t| Some((t, self.position_at(t)?.sub(&reference.position_at(t)?))))
+            .collect::<Option<Vec<(f64, Vector)>>>()?;
+        Some(Track { points })
+    }
+    /// Returns (timestamp, bearing rate in rad/s) for every sample of the track as seen from a
+    /// fixed sensor, e.g. for target motion analysis with a single sensor.
+    ///
+    /// The bearing is the azimut of the target measured counterclockwise from east, so a positive
+    /// rate means the bearing turns counterclockwise. The rate follows from the horizontal offset
+    /// r from the sensor and the velocity v of [Track::velocity_at] as
+    /// `(r_x * v_y - r_y * v_x) / (r_x² + r_y²)`. Samples without a velocity or directly above or
+    /// below the sensor are left out, so a track with less than two samples returns no rates.
+    /// # Examples
+    /// ```rust
+    /// let mut track = Track::new();
+    /// track.push(0.0, Vector::new(0.0, 100.0, 0.0));
+    /// track.push(1.0, Vector::new(10.0, 100.0, 0.0));
+    /// let rates = track.bearing_rate(&Vector::get_world_origin());
+    /// assert!(equal_within_delta(-0.1, rates[0].1));
+    /// ```
+    pub fn bearing_rate(&self, sensor: &Vector) -> Vec<(f64, f64)> {
+        self.points.iter()
+            .filter_map(|(t, position)| {
+                let velocity = self.velocity_at(*t)?;
+                let offset = position.sub(sensor);
+                let range_squared = offset.get_x().powi(2) + offset.get_y().powi(2);
+                if range_squared == 0.0 {
+                    return None;
+                }
+                let rate = (offset.get_x() * velocity.get_y() - offset.get_y() * velocity.get_x()) / range_squared;
+                Some((*t, rate))
+            })
+            .collect()
+    }
+    /// Returns the position at time t (in s) extrapolated from the velocity between the last two
+    /// samples, wrapped as a [VectorPoint] in the given coordinate system. The samples are taken
+    /// to be positions in that system. Returns None if the track has less than two samples or
+    /// the last two samples share their timestamp.
+    pub fn predict_point<'s, T: CoordinateSystem>(&self, sys: &'s T, t: f64) -> Option<VectorPoint<'s, T>> {
+        if self.points.len() < 2 {
+            return None;
+        }
+        let (t0, p0) = self.points[self.points.len() - 2];
+        let (t1, p1) = self.points[self.points.len() - 1];
+        if t1 <= t0 {
+            return None;
+        }
+        let velocity = p1.sub(&p0).scale(1.0 / (t1 - t0));
+        Some(VectorPoint::new(sys, p1.add(&velocity.scale(t - t1))))
+    }
+    /// Returns a quality score in range 0..1 for the track, higher being better.
+    ///
+    /// The score is the product of two factors. The first grows with the number n of velocity
+    /// estimates between consecutive samples as `n / (n + 5)`. The second rewards a consistent
+    /// velocity as `1 / (1 + variance)`, with variance being the mean squared deviation of the
+    /// velocity estimates from their mean in (m/s)². A track with less than two samples has no
+    /// velocity estimate and scores 0.
+    pub fn quality(&self) -> f64 {
+        let velocities: Vec<Vector> = self.points.windows(2)
+            .filter(|pair| pair[1].0 > pair[0].0)
+            .map(|pair| pair[1].1.sub(&pair[0].1).scale(1.0 / (pair[1].0 - pair[0].0)))
+            .collect();
+        if velocities.is_empty() {
+            return 0.0;
+        }
+        let count = velocities.len() as f64;
+        let mean = velocities.iter()
+            .fold(Vector::get_world_origin(), |sum, vel| sum.add(vel))
+            .scale(1.0 / count);
+        let variance = velocities.iter()
+            .map(|vel| vel.sub(&mean).dot(&vel.sub(&mean)))
+            .sum::<f64>() / count;
+
+        count / (count + 5.0) / (1.0 + variance)
+    }
+}
+
+/// Associates measurements with tracks by nearest neighbour and returns for every track the index
+/// of its measurement, or None if no measurement could be assigned.
+///
+/// The association is greedy: starting with the closest pair of track and measurement, each pair
+/// is assigned unless the track or the measurement is already taken. Pairs further apart than gate
+/// (in m) are never assigned, so every measurement is used by at most one track.
+/// # Examples
+/// ```rust
+/// let tracks = [Vector::new(0.0, 0.0, 0.0), Vector::new(100.0, 0.0, 0.0)];
+/// let measurements = [Vector::new(99.0, 1.0, 0.0), Vector::new(1.0, 0.0, 0.0)];
+/// assert_eq!(vec![Some(1), Some(0)], associate(&tracks, &measurements, 5.0));
+/// ```
+pub fn associate(tracks: &[Vector], measurements: &[Vector], gate: f64) -> Vec<Option<usize>> {
+    let mut pairs = Vec::new();
+    for (track_index, track) in tracks.iter().enumerate() {
+        for (measurement_index, measurement) in measurements.iter().enumerate() {
+            let distance = track.sub(measurement).magnitude();
+            if distance <= gate {
+                pairs.push((distance, track_index, measurement_index));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut assignment = vec![None; tracks.len()];
+    let mut measurement_taken = vec![false; measurements.len()];
+    for (_, track_index, measurement_index) in pairs {
+        if assignment[track_index].is_none() && !measurement_taken[measurement_index] {
+            assignment[track_index] = Some(measurement_index);
+            measurement_taken[measurement_index] = true;
+        }
+    }
+    assignment
+}
+
+/// Merges tracks that likely represent the same target and returns the resulting tracks.
+///
+/// Tracks whose latest positions are within distance_gate (in m) of each other, directly or
+/// through a chain of other tracks, form one group. Every group is combined into a single track
+/// holding the samples of all its members, where samples with the same timestamp are averaged.
+/// Empty tracks are never merged.
+pub fn merge_tracks(tracks: &[Track], distance_gate: f64) -> Vec<Track> {
+    let mut group_of: Vec<Option<usize>> = vec![None; tracks.len()];
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+
+    for start in 0..tracks.len() {
+        if group_of[start].is_some() {
+            continue;
+        }
+        let group_index = groups.len();
+        group_of[start] = Some(group_index);
+        let mut members = vec![start];
+        let mut next = 0;
+        while next < members.len() {
+            if let Some((_, position)) = tracks[members[next]].latest() {
+                for (other, track) in tracks.iter().enumerate() {
+                    if group_of[other].is_some() {
+                        continue;
+                    }
+                    if let Some((_, other_position)) = track.latest() {
+                        if position.sub(other_position).magnitude() <= distance_gate {
+                            group_of[other] = Some(group_index);
+                            members.push(other);
+                        }
+                    }
+                }
+            }
+            next += 1;
+        }
+        groups.push(members);
+    }
+
+    groups.iter().map(|members| {
+        let mut samples: Vec<(f64, Vector)> = members.iter()
+            .flat_map(|index| tracks[*index].points.iter().copied())
+            .collect();
+        samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut merged = Track::new();
+        let mut i = 0;
+        while i < samples.len() {
+            let t = samples[i].0;
+            let mut sum = Vector::get_world_origin();
+            let mut count = 0;
+            while i < samples.len() && samples[i].0 == t {
+                sum = sum.add(&samples[i].1);
+                count += 1;
+                i += 1;
+            }
+            merged.points.push((t, sum.scale(1.0 / count as f64)));
+        }
+        merged
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::coordinate_system::WorldCoordSystem;
+    use crate::utils::helper_functions::equal_within_delta;
+
+    #[test]
+    fn associate_separated_tracks(){
+        let tracks = [Vector::new(0.0, 0.0, -10.0), Vector::new(500.0, 500.0, -10.0)];
+        let measurements = [Vector::new(498.0, 503.0, -11.0), Vector::new(2.0, -1.0, -10.0)];
+        assert_eq!(vec![Some(1), Some(0)], associate(&tracks, &measurements, 10.0));
+    }
+
+    #[test]
+    fn associate_outside_gate(){
+        let tracks = [Vector::new(0.0, 0.0, -10.0), Vector::new(500.0, 500.0, -10.0)];
+        let measurements = [Vector::new(2.0, -1.0, -10.0), Vector::new(600.0, 500.0, -10.0)];
+        assert_eq!(vec![Some(0), None], associate(&tracks, &measurements, 10.0));
+    }
+
+    #[test]
+    fn associate_shared_measurement(){
+        // Both tracks are close to the same measurement, only the closer one gets it.
+        let tracks = [Vector::new(0.0, 0.0, 0.0), Vector::new(4.0, 0.0, 0.0)];
+        let measurements = [Vector::new(3.0, 0.0, 0.0)];
+        assert_eq!(vec![None, Some(0)], associate(&tracks, &measurements, 10.0));
+        assert_eq!(vec![None, None], associate(&tracks, &[], 10.0));
+    }
+
+    #[test]
+    fn track_push_sorted(){
+        let mut track = Track::new();
+        assert!(track.is_empty());
+        assert_eq!(None, track.latest());
+
+        track.push(3.0, Vector::new(3.0, 0.0, 0.0));
+        track.push(1.0, Vector::new(1.0, 0.0, 0.0));
+        track.push(2.0, Vector::new(2.0, 0.0, 0.0));
+
+        assert_eq!(3, track.len());
+        let times: Vec<f64> = track.get_points().iter().map(|(t, _)| *t).collect();
+        assert_eq!(vec![1.0, 2.0, 3.0], times);
+        assert_eq!(Some(&(3.0, Vector::new(3.0, 0.0, 0.0))), track.latest());
+    }
+
+    #[test]
+    fn merge_converging_tracks(){
+        let mut a = Track::new();
+        let mut b = Track::new();
+        let mut c = Track::new();
+        for t in 0..5 {
+            let t = t as f64;
+            a.push(t, Vector::new(10.0 * t, 2.0, -20.0));
+            b.push(t, Vector::new(10.0 * t, 6.0 - t, -20.0));
+            c.push(t, Vector::new(1000.0, 1000.0 + t, -50.0));
+        }
+
+        let merged = merge_tracks(&[a, b, c.clone()], 5.0);
+        assert_eq!(2, merged.len());
+        assert_eq!(5, merged[0].len());
+        assert_eq!(&(1.0, Vector::new(10.0, 3.5, -20.0)), &merged[0].get_points()[1]);
+        assert_eq!(&(4.0, Vector::new(40.0, 2.0, -20.0)), merged[0].latest().unwrap());
+        assert_eq!(c, merged[1]);
+    }
+
+    #[test]
+    fn merge_keeps_separate_tracks(){
+        let mut a = Track::new();
+        let mut b = Track::new();
+        a.push(0.0, Vector::new(0.0, 0.0, 0.0));
+        b.push(0.0, Vector::new(100.0, 0.0, 0.0));
+
+        let merged = merge_tracks(&[a.clone(), b.clone(), Track::new()], 5.0);
+        assert_eq!(vec![a, b, Track::new()], merged);
+    }
+
+    #[test]
+    fn quality_smooth_and_erratic(){
+        let mut smooth = Track::new();
+        let mut erratic = Track::new();
+        for step in 0..10 {
+            let t = step as f64;
+            smooth.push(t, Vector::new(5.0 * t, 2.0 * t, -30.0));
+            let jitter = if step % 2 == 0 { 4.0 } else { -4.0 };
+            erratic.push(t, Vector::new(5.0 * t + jitter, 2.0 * t - jitter, -30.0));
+        }
+
+        let smooth_quality = smooth.quality();
+        let erratic_quality = erratic.quality();
+        assert!(equal_within_delta(9.0 / 14.0, smooth_quality));
+        assert!(erratic_quality < smooth_quality);
+        assert!(erratic_quality > 0.0);
+    }
+
+    #[test]
+    fn quality_grows_with_samples(){
+        let mut track = Track::new();
+        assert_eq!(0.0, track.quality());
+        track.push(0.0, Vector::get_world_origin());
+        assert_eq!(0.0, track.quality());
+
+        let mut last_quality = 0.0;
+        for t in 1..6 {
+            track.push(t as f64, Vector::new(t as f64, 0.0, 0.0));
+            let quality = track.quality();
+            assert!(quality > last_quality);
+            assert!(quality < 1.0);
+            last_quality = quality;
+        }
+    }
+
+    #[test]
+    fn predict_point(){
+        let wcs = WorldCoordSystem::new();
+        let mut track = Track::new();
+        assert!(track.predict_point(&wcs, 10.0).is_none());
+        track.push(0.0, Vector::new(0.0, 0.0, -20.0));
+        assert!(track.predict_point(&wcs, 10.0).is_none());
+        track.push(1.0, Vector::new(3.0, 4.0, -20.0));
+        track.push(2.0, Vector::new(6.0, 8.0, -20.0));
+
+        let point = track.predict_point(&wcs, 10.0).unwrap();
+        assert_eq!(&Vector::new(30.0, 40.0, -20.0), point.get_vector());
+        assert_eq!(&wcs, point.get_cord_sys());
+    }
+
+    #[test]
+    fn position_at_interpolates(){
+        let mut track = Track::new();
+        assert_eq!(None, track.position_at(0.0));
+        track.push(10.0, Vector::new(100.0, 0.0, -20.0));
+        assert_eq!(Some(Vector::new(100.0, 0.0, -20.0)), track.position_at(0.0));
+
+        track.push(20.0, Vector::new(200.0, 50.0, -20.0));
+        track.push(40.0, Vector::new(200.0, 250.0, -40.0));
+        assert_eq!(Some(Vector::new(150.0, 25.0, -20.0)), track.position_at(15.0));
+        assert_eq!(Some(Vector::new(200.0, 150.0, -30.0)), track.position_at(30.0));
+        assert_eq!(Some(Vector::new(200.0, 50.0, -20.0)), track.position_at(20.0));
+
+        // Outside the track the first or last leg is extrapolated.
+        assert_eq!(Some(Vector::new(50.0, -25.0, -20.0)), track.position_at(5.0));
+        assert_eq!(Some(Vector::new(200.0, 350.0, -50.0)), track.position_at(50.0));
+    }
+
+    #[test]
+    fn velocity_at_constant_velocity(){
+        let mut track = Track::new();
+        assert_eq!(None, track.velocity_at(0.0));
+        for step in 0..6 {
+            let t = 2.0 * step as f64;
+            track.push(t, Vector::new(3.0 * t, -1.5 * t, -10.0));
+        }
+        for t in [-5.0, 0.0, 1.0, 4.0, 7.3, 10.0, 30.0].iter() {
+            assert_eq!(Some(Vector::new(3.0, -1.5, 0.0)), track.velocity_at(*t));
+        }
+
+        let mut same_time = Track::new();
+        same_time.push(1.0, Vector::new(0.0, 0.0, 0.0));
+        same_time.push(1.0, Vector::new(5.0, 0.0, 0.0));
+        assert_eq!(None, same_time.velocity_at(1.0));
+    }
+
+    #[test]
+    fn relative_to_parallel_tracks(){
+        let mut target = Track::new();
+        let mut own_ship = Track::new();
+        for step in 0..6 {
+            let t = 10.0 * step as f64;
+            target.push(t, Vector::new(500.0 + 4.0 * t, 200.0 + 3.0 * t, -50.0));
+        }
+        // The own ship samples at other times and runs beyond the target track.
+        for step in 0..10 {
+            let t = 5.0 + 10.0 * step as f64;
+            own_ship.push(t, Vector::new(4.0 * t, 3.0 * t, 0.0));
+        }
+
+        let relative = target.relative_to(&own_ship).unwrap();
+        let times: Vec<f64> = relative.get_points().iter().map(|(t, _)| *t).collect();
+        assert_eq!(vec![5.0, 10.0, 15.0, 20.0, 25.0, 30.0, 35.0, 40.0, 45.0, 50.0], times);
+        for (_, position) in relative.get_points() {
+            assert!(position.sub(&Vector::new(500.0, 200.0, -50.0)).magnitude() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn relative_to_without_overlap(){
+        let mut early = Track::new();
+        early.push(0.0, Vector::new(0.0, 0.0, 0.0));
+        early.push(10.0, Vector::new(10.0, 0.0, 0.0));
+        let mut late = Track::new();
+        late.push(20.0, Vector::new(0.0, 0.0, 0.0));
+        late.push(30.0, Vector::new(10.0, 0.0, 0.0));
+
+        assert_eq!(None, early.relative_to(&late));
+        assert_eq!(None, late.relative_to(&early));
+        assert_eq!(None, early.relative_to(&Track::new()));
+        assert_eq!(None, Track::new().relative_to(&early));
+    }
+
+    #[test]
+    fn bearing_rate_crossing_target(){
+        // The target passes the sensor 100 m to the north, heading east at 10 m/s.
+        let sensor = Vector::new(0.0, 0.0, -30.0);
+        let mut track = Track::new();
+        for step in 0..=40 {
+            let t = 5.0 * step as f64;
+            track.push(t, Vector::new(-1000.0 + 10.0 * t, 100.0, -10.0));
+        }
+
+        let rates = track.bearing_rate(&sensor);
+        assert_eq!(41, rates.len());
+        assert!(rates.iter().all(|(_, rate)| *rate < 0.0));
+
+        // The bearing turns fastest at the closest approach after 100 s with v / range.
+        let (peak_time, peak_rate) = rates.iter()
+            .copied()
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .unwrap();
+        assert_eq!(100.0, peak_time);
+        assert!(equal_within_delta(-0.1, peak_rate));
+        assert!(rates[0].1.abs() < 0.001);
+    }
+
+    #[test]
+    fn bearing_rate_degenerate(){
+        let mut track = Track::new();
+        track.push(0.0, Vector::new(0.0, 0.0, -10.0));
+        assert!(track.bearing_rate(&Vector::get_world_origin()).is_empty());
+
+        // The sample directly below the sensor has no bearing.
+        track.push(1.0, Vector::new(5.0, 0.0, -10.0));
+        let rates = track.bearing_rate(&Vector::get_world_origin());
+        assert_eq!(vec![(1.0, 0.0)], rates);
+    }
+}
@@ -0,0 +1,146 @@
+//! This module contains a vector field sampled on a regular grid, e.g. to model currents.
+
+use crate::physics::vectors::Vector;
+
+/// A vector field given by its values on the nodes of a regular, axis aligned grid.
+/// * **origin** is the position of the first node
+/// * **spacing** is the distance between neighbouring nodes along each axis
+/// * **dims** is the number of nodes along x, y and z
+/// * **data** holds the value of every node with x varying fastest, so the node (i, j, k) is
+///   found at index `i + dims.0 * (j + dims.1 * k)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorField {
+    origin: Vector,
+    spacing: Vector,
+    dims: (usize, usize, usize),
+    data: Vec<Vector>
+}
+
+impl VectorField {
+    /// Generates a new vector field, or None if the number of values does not match dims or a
+    /// spacing is not positive.
+    /// # Examples
+    /// ```rust
+    /// let field = VectorField::new(Vector::zero(), Vector::new(10.0, 10.0, 5.0), (2, 1, 1),
+    ///                              vec![Vector::zero(), Vector::unit_x()]);
+    /// assert!(field.is_some());
+    /// ```
+    pub fn new(origin: Vector, spacing: Vector, dims: (usize, usize, usize),
+               data: Vec<Vector>) -> Option<VectorField> {
+        let positive_spacing = (0..3).all(|axis| spacing[axis] > 0.0);
+        if !positive_spacing || data.len() != dims.0 * dims.1 * dims.2 {
+            return None;
+        }
+        Some(VectorField { origin, spacing, dims, data })
+    }
+    /// Returns the value of the node (i, j, k). The indices must lie inside dims.
+    fn node(&self, i: usize, j: usize, k: usize) -> Vector {
+        self.data[i + self.dims.0 * (j + self.dims.1 * k)]
+    }
+    /// Returns the value of the field at pos, interpolated trilinearly between the eight nodes
+    /// of the grid cell containing pos, or None if pos lies outside the grid. Along an axis with a
+    /// single node, only positions exactly on that node lie inside the grid.
+    /// # Examples
+    /// ```rust
+    /// let field = VectorField::new(Vector::zero(), Vector::new(10.0, 10.0, 5.0), (2, 1, 1),
+    ///                              vec![Vector::zero(), Vector::unit_x()]).unwrap();
+    /// assert_eq!(Some(Vector::new(0.25, 0.0, 0.0)), field.sample(&Vector::new(2.5, 0.0, 0.0)));
+    /// assert_eq!(None, field.sample(&Vector::new(12.0, 0.0, 0.0)));
+    /// ```
+    pub fn sample(&self, pos: &Vector) -> Option<Vector> {
+        let dims = [self.dims.0, self.dims.1, self.dims.2];
+        let mut lower = [0; 3];
+        let mut fraction = [0.0; 3];
+        for axis in 0..3 {
+            let grid_pos = (pos[axis] - self.origin[axis]) / self.spacing[axis];
+            let max_index = dims[axis].checked_sub(1)?;
+            if !(0.0..=max_index as f64).contains(&grid_pos) {
+                return None;
+            }
+            lower[axis] = (grid_pos.floor() as usize).min(max_index.saturating_sub(1));
+            fraction[axis] = grid_pos - lower[axis] as f64;
+        }
+        let upper = |axis: usize| (lower[axis] + 1).min(dims[axis] - 1);
+
+        let mut result = Vector::zero();
+        for (k, weight_z) in [(lower[2], 1.0 - fraction[2]), (upper(2), fraction[2])].iter() {
+            for (j, weight_y) in [(lower[1], 1.0 - fraction[1]), (upper(1), fraction[1])].iter() {
+                for (i, weight_x) in [(lower[0], 1.0 - fraction[0]), (upper(0), fraction[0])].iter() {
+                    let weight = weight_x * weight_y * weight_z;
+                    result = result.add(&self.node(*i, *j, *k).scale(weight));
+                }
+            }
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::helper_functions::assert_vec_eq;
+
+    /// A 3 x 2 x 2 field with spacing (10, 20, 5) whose value at every node is (x, y, z) of the
+    /// node's position, so trilinear interpolation reproduces the position exactly.
+    fn position_field() -> VectorField {
+        let origin = Vector::new(100.0, -50.0, -30.0);
+        let spacing = Vector::new(10.0, 20.0, 5.0);
+        let mut data = Vec::new();
+        for k in 0..2 {
+            for j in 0..2 {
+                for i in 0..3 {
+                    data.push(origin.add(&Vector::new(i as f64 * 10.0, j as f64 * 20.0, k as f64 * 5.0)));
+                }
+            }
+        }
+        VectorField::new(origin, spacing, (3, 2, 2), data).unwrap()
+    }
+
+    #[test]
+    fn sample_at_nodes(){
+        let field = position_field();
+        for node in [Vector::new(100.0, -50.0, -30.0), Vector::new(110.0, -30.0, -30.0),
+                     Vector::new(120.0, -30.0, -25.0), Vector::new(120.0, -50.0, -25.0)].iter() {
+            assert_vec_eq(node, &field.sample(node).unwrap());
+        }
+    }
+
+    #[test]
+    fn sample_at_cell_center(){
+        let field = position_field();
+        let center = Vector::new(115.0, -40.0, -27.5);
+        assert_vec_eq(&center, &field.sample(&center).unwrap());
+
+        // The center of a cell is the mean of its eight corners.
+        let corners = VectorField::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), (2, 2, 2),
+                                       vec![Vector::unit_x(), Vector::zero(), Vector::zero(), Vector::zero(),
+                                            Vector::unit_z(), Vector::zero(), Vector::zero(), Vector::unit_y()])
+            .unwrap();
+        assert_vec_eq(&Vector::new(0.125, 0.125, 0.125), &corners.sample(&Vector::new(0.5, 0.5, 0.5)).unwrap());
+    }
+
+    #[test]
+    fn sample_outside_grid(){
+        let field = position_field();
+        assert_eq!(None, field.sample(&Vector::new(99.0, -40.0, -27.5)));
+        assert_eq!(None, field.sample(&Vector::new(115.0, -29.0, -27.5)));
+        assert_eq!(None, field.sample(&Vector::new(115.0, -40.0, -24.0)));
+    }
+
+    #[test]
+    fn single_node_axis(){
+        let field = VectorField::new(Vector::zero(), Vector::new(10.0, 10.0, 10.0), (2, 2, 1),
+                                     vec![Vector::zero(), Vector::unit_x(), Vector::unit_y(), Vector::new(1.0, 1.0, 0.0)])
+            .unwrap();
+        assert_vec_eq(&Vector::new(0.5, 0.5, 0.0), &field.sample(&Vector::new(5.0, 5.0, 0.0)).unwrap());
+        assert_eq!(None, field.sample(&Vector::new(5.0, 5.0, 1.0)));
+    }
+
+    #[test]
+    fn invalid_fields(){
+        assert_eq!(None, VectorField::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), (2, 2, 2), vec![Vector::zero()]));
+        assert_eq!(None, VectorField::new(Vector::zero(), Vector::new(1.0, 0.0, 1.0), (1, 1, 1), vec![Vector::zero()]));
+        let empty = VectorField::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), (0, 0, 0), Vec::new()).unwrap();
+        assert_eq!(None, empty.sample(&Vector::zero()));
+    }
+}
@@ -1,5 +1,7 @@
 use crate::physics::vectors::{PolarVec, Vector};
-use crate::physics::coordinate_system::CoordinateSystem;
+use crate::physics::coordinate_system::{CoordinateSystem, WorldCoordSystem};
+use crate::physics::kinematics::extrapolate;
+use std::f64::consts::{PI, TAU};
 
 /// This struct describes a point in space in a given coordinate system by
 /// using a cartesian vector.
@@ -10,19 +12,78 @@ pub struct VectorPoint<'a, T>
     vector: Vector
 }
 
-impl<T: CoordinateSystem> VectorPoint<'_, T> {
+impl<'a, T: CoordinateSystem> VectorPoint<'a, T> {
     // Creates a new VectorPoint out of a coordinate system and a Vector.
-    pub fn new(cord_sys: &T, vector: Vector) -> VectorPoint<T>{
+    pub fn new(cord_sys: &T, vector: Vector) -> VectorPoint<'_, T>{
         VectorPoint {cord_sys, vector}
     }
     // Returns a reference to the coordinate system in which this VectorPoint is valid.
     pub fn get_cord_sys(&self) -> &T {
-        &self.cord_sys
+        self.cord_sys
     }
     // Returns the vector that defines the point
     pub fn get_vector(&self) -> &Vector {
         &self.vector
     }
+    /// Returns the vector of this point in world coordinates.
+    pub fn get_world_vector(&self) -> Vector {
+        self.cord_sys.transform_vector_into_world_coords(&self.vector)
+    }
+    /// Returns this point transformed into world coordinates. The returned point borrows the
+    /// shared world coordinate system ([WorldCoordSystem::shared]), so it does not depend on the
+    /// lifetime of this point's coordinate system.
+    pub fn to_world_point(&self) -> VectorPoint<'static, WorldCoordSystem> {
+        VectorPoint::new(WorldCoordSystem::shared(), self.get_world_vector())
+    }
+    /// Returns the distance in m between this point and another point, which may be defined in a
+    /// different coordinate system. Both points are transformed into world coordinates first.
+    pub fn distance_to(&self, other: &VectorPoint<'_, impl CoordinateSystem>) -> f64 {
+        self.get_world_vector().sub(&other.get_world_vector()).magnitude()
+    }
+    /// Returns the time in s that sound needs to travel from this point to another point at the
+    /// given sound speed in m/s.
+    pub fn travel_time(&self, other: &VectorPoint<'_, impl CoordinateSystem>, sound_speed_mps: f64) -> f64 {
+        self.distance_to(other) / sound_speed_mps
+    }
+    /// Returns the time in s that an echo needs from this point to another point and back at the
+    /// given sound speed in m/s.
+    pub fn two_way_travel_time(&self, other: &VectorPoint<'_, impl CoordinateSystem>, sound_speed_mps: f64) -> f64 {
+        2.0 * self.travel_time(other, sound_speed_mps)
+    }
+    /// Returns the look direction from this point to another point in world coordinates as a
+    /// [PolarVec]: the radius is the range, phi the azimut measured counterclockwise from east and
+    /// theta the polar angle measured from straight up, so a target at the same height has a
+    /// theta of pi/2. The direction to a coincident point is undefined, so it is returned as
+    /// PolarVec::new(0.0, 0.0, 0.0).
+    pub fn bearing_to(&self, other: &VectorPoint<'_, impl CoordinateSystem>) -> PolarVec {
+        other.get_world_vector().sub(&self.get_world_vector()).to_polar_vector()
+    }
+    /// Returns the bearing of another point relative to the bow of a platform at this point in rad,
+    /// in range (-pi..pi].
+    ///
+    /// Unlike [VectorPoint::bearing_to] this follows the nautical convention: the heading and the
+    /// relative bearing are measured clockwise from north (the world y axis) and from the bow, as
+    /// the course of [velocity_to_sog_cog](crate::physics::kinematics::velocity_to_sog_cog). A
+    /// target dead ahead lies at 0, on the starboard beam at pi/2, on the port beam at -pi/2 and
+    /// astern at pi.
+    /// # Examples
+    /// ```rust
+    /// let wcs = WorldCoordSystem::new();
+    /// let ship = VectorPoint::new(&wcs, Vector::get_world_origin());
+    /// let target = VectorPoint::new(&wcs, Vector::new(100.0, 0.0, 0.0));
+    /// assert_eq!(FRAC_PI_2, ship.relative_bearing(&target, 0.0));
+    /// ```
+    pub fn relative_bearing(&self, target: &VectorPoint<'_, impl CoordinateSystem>, heading_rad: f64) -> f64 {
+        let offset = target.get_world_vector().sub(&self.get_world_vector());
+        let bearing = offset.get_x().atan2(offset.get_y());
+        PI - (PI - (bearing - heading_rad)).rem_euclid(TAU)
+    }
+    /// Returns the point a contact at this point reaches after dt seconds at constant velocity,
+    /// see [extrapolate]. The velocity is given in the coordinate system of this point, which the
+    /// returned point keeps.
+    pub fn extrapolate(&self, velocity: &Vector, dt: f64) -> VectorPoint<'a, T> {
+        VectorPoint { cord_sys: self.cord_sys, vector: extrapolate(&self.vector, velocity, dt) }
+    }
 }
 /// This struct describes a point in space in a given coordinate system by
 /// using a polar vector.
@@ -35,12 +96,12 @@ pub struct PolarVectorPoint<'a, T>
 
 impl<T: CoordinateSystem> PolarVectorPoint<'_, T> {
     // Creates a new VectorPoint out of a coordinate system and a PolarVector.
-    pub fn new(cord_sys: &T, vector: PolarVec) -> PolarVectorPoint<T>{
+    pub fn new(cord_sys: &T, vector: PolarVec) -> PolarVectorPoint<'_, T>{
         PolarVectorPoint {cord_sys, vector}
     }
     // Returns a reference to the coordinate system in which this PolarVectorPoint is valid.
     pub fn get_cord_sys(&self) -> &T {
-        &self.cord_sys
+        self.cord_sys
     }
     // Returns the vector that defines the point
     pub fn get_vector(&self) -> &PolarVec {
@@ -55,6 +116,8 @@ mod tests {
     mod vector_point {
         use super::*;
         use crate::physics::coordinate_system::{WorldCoordSystem, GeneralCoordSystem};
+        use crate::utils::helper_functions::equal_within_delta;
+        use std::f64::consts::{PI, FRAC_PI_2};
 
         #[test]
         fn creation(){
@@ -70,6 +133,130 @@ mod tests {
             assert_eq!(&vec, vp.get_vector());
             assert_eq!(&gcs, vp.get_cord_sys());
         }
+
+        #[test]
+        fn to_world_point(){
+            let wcs = WorldCoordSystem::new();
+            let ship = GeneralCoordSystem::new("ship".to_string(), &wcs, Vector::new(100.0, 50.0, 0.0));
+            let sonar = GeneralCoordSystem::new("sonar".to_string(), &ship, Vector::new(0.0, 5.0, -3.0));
+            let point = VectorPoint::new(&sonar, Vector::new(10.0, 0.0, -20.0));
+
+            let world_point = point.to_world_point();
+            assert_eq!(&Vector::new(110.0, 55.0, -23.0), world_point.get_vector());
+            assert_eq!(&wcs, world_point.get_cord_sys());
+            assert_eq!(0.0, point.distance_to(&world_point));
+        }
+
+        #[test]
+        fn bearing_to(){
+            let wcs = WorldCoordSystem::new();
+            let ship = GeneralCoordSystem::new("ship".to_string(), &wcs, Vector::new(100.0, 50.0, -10.0));
+            let observer = VectorPoint::new(&ship, Vector::get_world_origin());
+
+            let east = VectorPoint::new(&wcs, Vector::new(300.0, 50.0, -10.0));
+            assert_eq!(PolarVec::new(200.0, 0.0, FRAC_PI_2), observer.bearing_to(&east));
+
+            let north = VectorPoint::new(&wcs, Vector::new(100.0, 80.0, -10.0));
+            assert_eq!(PolarVec::new(30.0, FRAC_PI_2, FRAC_PI_2), observer.bearing_to(&north));
+
+            let above = VectorPoint::new(&ship, Vector::new(0.0, 0.0, 10.0));
+            assert_eq!(PolarVec::new(10.0, 0.0, 0.0), observer.bearing_to(&above));
+
+            let below = VectorPoint::new(&ship, Vector::new(0.0, 0.0, -25.0));
+            assert_eq!(PolarVec::new(25.0, 0.0, PI), observer.bearing_to(&below));
+            assert_ne!(observer.bearing_to(&above), observer.bearing_to(&below));
+
+            let south = VectorPoint::new(&wcs, Vector::new(100.0, 20.0, -10.0));
+            let bearing = observer.bearing_to(&south);
+            assert!(equal_within_delta(PI + FRAC_PI_2, bearing.get_phi_in_rad()));
+            assert_eq!(PolarVec::new(30.0, PI + FRAC_PI_2, FRAC_PI_2), bearing);
+
+            let west = VectorPoint::new(&ship, Vector::new(-40.0, 0.0, 0.0));
+            let bearing = observer.bearing_to(&west);
+            assert!(equal_within_delta(PI, bearing.get_phi_in_rad()));
+            assert_eq!(PolarVec::new(40.0, PI, FRAC_PI_2), bearing);
+
+            let coincident = VectorPoint::new(&wcs, Vector::new(100.0, 50.0, -10.0));
+            let bearing = observer.bearing_to(&coincident);
+            assert_eq!(0.0, bearing.get_radius());
+            assert_eq!(0.0, bearing.get_phi_in_rad());
+            assert_eq!(0.0, bearing.get_theta_in_rad());
+        }
+
+        #[test]
+        fn relative_bearing(){
+            let wcs = WorldCoordSystem::new();
+            let ship = GeneralCoordSystem::new("ship".to_string(), &wcs, Vector::new(100.0, 100.0, 0.0));
+            let own = VectorPoint::new(&ship, Vector::get_world_origin());
+            // The ship heads north east.
+            let heading = FRAC_PI_2 / 2.0;
+
+            let ahead = VectorPoint::new(&wcs, Vector::new(200.0, 200.0, -30.0));
+            let starboard = VectorPoint::new(&wcs, Vector::new(200.0, 0.0, 0.0));
+            let port = VectorPoint::new(&ship, Vector::new(-50.0, 50.0, 0.0));
+            let astern = VectorPoint::new(&wcs, Vector::new(0.0, 0.0, 0.0));
+
+            assert!(equal_within_delta(0.0, own.relative_bearing(&ahead, heading)));
+            assert!(equal_within_delta(FRAC_PI_2, own.relative_bearing(&starboard, heading)));
+            assert!(equal_within_delta(-FRAC_PI_2, own.relative_bearing(&port, heading)));
+            assert!(equal_within_delta(PI, own.relative_bearing(&astern, heading).abs()));
+
+            // Exactly astern is reported as +pi, never as -pi.
+            let south = VectorPoint::new(&wcs, Vector::new(100.0, 0.0, 0.0));
+            assert_eq!(PI, own.relative_bearing(&south, 0.0));
+            assert_eq!(PI, own.relative_bearing(&ahead, heading + PI));
+        }
+
+        #[test]
+        fn travel_time(){
+            let wcs = WorldCoordSystem::new();
+            let ship = GeneralCoordSystem::new("ship".to_string(), &wcs, Vector::new(0.0, 0.0, -5.0));
+            let sonar = VectorPoint::new(&ship, Vector::get_world_origin());
+            let target = VectorPoint::new(&wcs, Vector::new(1800.0, 2400.0, -5.0));
+
+            assert_eq!(2.0, sonar.travel_time(&target, 1500.0));
+            assert_eq!(2.0, target.travel_time(&sonar, 1500.0));
+            assert_eq!(4.0, sonar.two_way_travel_time(&target, 1500.0));
+        }
+
+        #[test]
+        fn extrapolate(){
+            let wcs = WorldCoordSystem::new();
+            let ship = GeneralCoordSystem::new("ship".to_string(), &wcs, Vector::new(100.0, 0.0, 0.0));
+            let contact = VectorPoint::new(&ship, Vector::new(10.0, 20.0, -5.0));
+
+            let later = contact.extrapolate(&Vector::new(1.0, -2.0, 0.0), 10.0);
+            assert_eq!(&Vector::new(20.0, 0.0, -5.0), later.get_vector());
+            assert_eq!(&ship, later.get_cord_sys());
+            assert_eq!(Vector::new(120.0, 0.0, -5.0), later.get_world_vector());
+            assert_eq!(contact, contact.extrapolate(&Vector::new(1.0, -2.0, 0.0), 0.0));
+        }
+
+        #[test]
+        fn distance_same_frame(){
+            let wcs = WorldCoordSystem::new();
+            let gcs = GeneralCoordSystem::new("gcs".to_string(), &wcs, Vector::new(10.0, 90.0, 90.0));
+            let a = VectorPoint::new(&gcs, Vector::new(1.0, 2.0, 3.0));
+            let b = VectorPoint::new(&gcs, Vector::new(4.0, 6.0, 15.0));
+
+            assert_eq!(13.0, a.distance_to(&b));
+            assert_eq!(13.0, b.distance_to(&a));
+            assert_eq!(0.0, a.distance_to(&a));
+        }
+
+        #[test]
+        fn distance_different_frames(){
+            let wcs = WorldCoordSystem::new();
+            let ship_a = GeneralCoordSystem::new("ship_a".to_string(), &wcs, Vector::new(100.0, 0.0, 0.0));
+            let ship_b = GeneralCoordSystem::new("ship_b".to_string(), &wcs, Vector::new(0.0, 100.0, 0.0));
+            let a = VectorPoint::new(&ship_a, Vector::new(-100.0, 30.0, 0.0));
+            let b = VectorPoint::new(&ship_b, Vector::new(40.0, -100.0, 0.0));
+
+            assert_eq!(50.0, a.distance_to(&b));
+
+            let world_point = VectorPoint::new(&wcs, Vector::new(0.0, 30.0, 0.0));
+            assert_eq!(0.0, a.distance_to(&world_point));
+        }
     }
 
     mod polar_vector_point {
@@ -41,52 +41,175 @@
 //! This crate contains the implementations of cartesian and polar vectors.
 
 use std::cmp::Ordering;
-use std::fmt::{Display, Formatter};
+use std::fmt::{Debug, Display, Formatter};
+use std::error::Error;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Neg, Rem, Sub};
 use crate::constants::WORLD_ORIGIN;
-use std::f64::consts::{PI, FRAC_PI_2, TAU};
+use std::f64::consts::PI;
 use crate::utils::helper_functions::*;
 
 
-/// A cartesian vector from three double (```f64```) values.
+/// The floating point types the components of [GenericVector] and [GenericPolarVec] can be stored in: ```f64```
+/// for precise ranging math and ```f32``` for memory bound point clouds.
+pub trait Float: Copy + Debug + Display + PartialOrd + Add<Output = Self> + Sub<Output = Self>
+    + Mul<Output = Self> + Div<Output = Self> + Rem<Output = Self> + Neg<Output = Self> + AddAssign {
+    const ZERO: Self;
+    const ONE: Self;
+    const PI: Self;
+    const TAU: Self;
+
+    /// Converts a double value, rounding it to the nearest value of this type.
+    fn from_f64(value: f64) -> Self;
+    /// Converts the value into a double value without loss.
+    fn to_f64(self) -> f64;
+    /// Returns the bits of the value, widened to 64 bit.
+    fn to_bits_u64(self) -> u64;
+    fn total_cmp(&self, other: &Self) -> Ordering;
+    fn is_finite(self) -> bool;
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
+    fn to_radians(self) -> Self;
+    fn to_degrees(self) -> Self;
+}
+
+/// Implements [Float] by forwarding to the inherent methods of the primitive type.
+macro_rules! impl_float {
+    ($float:ident) => {
+        impl Float for $float {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const PI: Self = std::$float::consts::PI;
+            const TAU: Self = std::$float::consts::TAU;
+
+            fn from_f64(value: f64) -> Self { value as $float }
+            fn to_f64(self) -> f64 { f64::from(self) }
+            fn to_bits_u64(self) -> u64 { u64::from($float::to_bits(self)) }
+            fn total_cmp(&self, other: &Self) -> Ordering { $float::total_cmp(self, other) }
+            fn is_finite(self) -> bool { $float::is_finite(self) }
+            fn abs(self) -> Self { $float::abs(self) }
+            fn sqrt(self) -> Self { $float::sqrt(self) }
+            fn sin(self) -> Self { $float::sin(self) }
+            fn cos(self) -> Self { $float::cos(self) }
+            fn acos(self) -> Self { $float::acos(self) }
+            fn atan2(self, other: Self) -> Self { $float::atan2(self, other) }
+            fn min(self, other: Self) -> Self { $float::min(self, other) }
+            fn max(self, other: Self) -> Self { $float::max(self, other) }
+            fn rem_euclid(self, rhs: Self) -> Self { $float::rem_euclid(self, rhs) }
+            fn to_radians(self) -> Self { $float::to_radians(self) }
+            fn to_degrees(self) -> Self { $float::to_degrees(self) }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+/// A cartesian vector from three floating point values of type ```F```.
 /// * **x** points east
 /// * **y** points north
 /// * **z** points up
-#[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
-pub struct Vector {
-    x: f64,
-    y: f64,
-    z: f64
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GenericVector<F: Float> {
+    x: F,
+    y: F,
+    z: F
 }
-impl Eq for Vector{}
+impl<F: Float> Eq for GenericVector<F>{}
 
-impl Ord for Vector{
-    fn cmp(&self, other: &Self) -> Ordering {
-        return if self.eq(&other) {
-            Ordering::Equal
-        } else if self.x.ne(&other.x) {
-            if self.x > other.x {
-                Ordering::Greater
-            } else { Ordering::Less }
-        } else if self.y.ne(&other.y) {
-            if self.y > other.y {
-                Ordering::Greater
-            } else { Ordering::Less }
-        } else {
-            if self.z > other.z {
-                Ordering::Greater
-            } else { Ordering::Less }
+/// A cartesian vector of double (```f64```) values, the vector used throughout the crate.
+pub type Vector = GenericVector<f64>;
+/// Another name for [Vector], to pair with [Vec32].
+pub type Vec64 = Vector;
+/// A cartesian vector of single (```f32```) values, for large point clouds that do not need the
+/// precision of [Vec64].
+pub type Vec32 = GenericVector<f32>;
+
+/// Hashes the bit patterns of the components, so vectors can be used as keys of a HashMap or
+/// HashSet. A component of -0.0 is hashed as 0.0, since both are equal. NaN components follow
+/// their bit representation, but a vector holding NaN is never equal to any vector and thus
+/// never found again as a key.
+impl<F: Float> Hash for GenericVector<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for component in [self.x, self.y, self.z].iter() {
+            without_negative_zero(*component).to_bits_u64().hash(state);
+        }
+    }
+}
+
+impl<F: Float> PartialOrd for GenericVector<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Returns the component for the index 0 (x), 1 (y) or 2 (z). Like slices, it panics for any
+/// other index.
+impl<F: Float> Index<usize> for GenericVector<F> {
+    type Output = F;
+
+    fn index(&self, index: usize) -> &F {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("index out of bounds: the len is 3 but the index is {}", index),
         }
     }
 }
 
-impl Display for Vector {
+/// Returns the component for the index 0 (x), 1 (y) or 2 (z) for writing. Like slices, it panics
+/// for any other index.
+impl<F: Float> IndexMut<usize> for GenericVector<F> {
+    fn index_mut(&mut self, index: usize) -> &mut F {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("index out of bounds: the len is 3 but the index is {}", index),
+        }
+    }
+}
+
+/// Orders vectors by x, then y, then z. The components are compared with [f64::total_cmp], so the
+/// order is total even for NaN components, which sort after all numbers if they are positive and
+/// before all numbers if they are negative. -0.0 is treated as 0.0, matching equality. Two vectors
+/// with the same NaN component compare as equal, although they are not equal by [PartialEq].
+impl<F: Float> Ord for GenericVector<F>{
+    fn cmp(&self, other: &Self) -> Ordering {
+        without_negative_zero(self.x).total_cmp(&without_negative_zero(other.x))
+            .then_with(|| without_negative_zero(self.y).total_cmp(&without_negative_zero(other.y)))
+            .then_with(|| without_negative_zero(self.z).total_cmp(&without_negative_zero(other.z)))
+    }
+}
+
+/// Returns the value with -0.0 replaced by 0.0, so that both zeros hash and order alike.
+fn without_negative_zero<F: Float>(value: F) -> F {
+    if value == F::ZERO { F::ZERO } else { value }
+}
+
+/// The number of decimal places the Display implementations use if the formatter sets no precision.
+const DEFAULT_DISPLAY_PRECISION: usize = 3;
+
+/// Displays the components with the precision of the formatter, e.g. `format!("{:.1}", vec)`,
+/// or with [DEFAULT_DISPLAY_PRECISION] decimal places.
+impl<F: Float> Display for GenericVector<F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "[X: {:?} m, Y: {:?} m, Z: {:?} m]", self.x, self.y, self.z)
+        let precision = f.precision().unwrap_or(DEFAULT_DISPLAY_PRECISION);
+        write!(f, "[X: {:.*} m, Y: {:.*} m, Z: {:.*} m]", precision, self.x, precision, self.y, precision, self.z)
     }
 }
 
-impl Vector {
+impl<F: Float> GenericVector<F> {
     /// Generates a new instance of vector.
     /// # Examples
     /// ```rust
@@ -95,22 +218,8 @@ impl Vector {
     /// assert_eq!(5.0, vec.y);
     /// assert_eq!(-4.0, vec.z);
     /// ```
-    pub fn new(x: f64, y: f64, z: f64) -> Vector {
-        Vector{x,y,z}
-    }
-    /// Returns a vector containing the [world origin] (0,0,0).
-    /// # Examples
-    /// ```rust
-    /// let vec = Vector::get_world_origin();
-    /// assert_eq!(0.0, vec.x);
-    /// assert_eq!(0.0, vec.y);
-    /// assert_eq!(0.0, vec.z);
-    /// ```
-    ///
-    /// [world origin]: GetLinkLocation
-    pub fn get_world_origin() -> Vector {
-        let (x,y,z) = WORLD_ORIGIN;
-        Vector{x,y,z}
+    pub const fn new(x: F, y: F, z: F) -> GenericVector<F> {
+        GenericVector{x,y,z}
     }
     /// Returns the x value of a vector.
     /// # Examples
@@ -118,7 +227,7 @@ impl Vector {
     /// let vec = Vector::new(3.0, 5.0, -4.0);
     /// assert_eq!(3.0, vec.get_x());
     /// ```
-    pub fn get_x(&self) -> f64 {
+    pub fn get_x(&self) -> F {
         self.x
     }
     /// Returns the y value of a vector.
@@ -127,7 +236,7 @@ impl Vector {
     /// let vec = Vector::new(3.0, 5.0, -4.0);
     /// assert_eq!(5.0, vec.get_y());
     /// ```
-    pub fn get_y(&self) -> f64 {
+    pub fn get_y(&self) -> F {
         self.y
     }
     /// Returns the z value of a vector.
@@ -136,7 +245,7 @@ impl Vector {
     /// let vec = Vector::new(3.0, 5.0, -4.0);
     /// assert_eq!(-4.0, vec.get_z());
     /// ```
-    pub fn get_z(&self) -> f64 {
+    pub fn get_z(&self) -> F {
         self.z
     }
     /// Returns a new vector created from the added values from another vector.
@@ -149,8 +258,8 @@ impl Vector {
     /// assert_eq!(7.0, vec.get_y());
     /// assert_eq!(-1.0, vec.get_z());
     /// ```
-    pub fn add(&self, other: &Self) -> Vector {
-        Vector {
+    pub fn add(&self, other: &Self) -> GenericVector<F> {
+        GenericVector {
             x: self.x + other.x,
             y: self.y + other.y,
             z: self.z + other.z,
@@ -166,13 +275,173 @@ impl Vector {
     /// assert_eq!(3.0, vec.get_y());
     /// assert_eq!(-7.0, vec.get_z>());
     /// ```
-    pub fn sub(&self, other: &Self) -> Vector {
-        Vector {
+    pub fn sub(&self, other: &Self) -> GenericVector<F> {
+        GenericVector {
             x: self.x - other.x,
             y: self.y - other.y,
             z: self.z - other.z,
         }
     }
+    /// Returns a new vector with every component multiplied by factor.
+    /// # Examples
+    /// ```rust
+    /// let vec = Vector::new(3.0, 5.0, -4.0);
+    /// let vec = vec.scale(2.0);
+    /// assert_eq!(6.0, vec.get_x());
+    /// assert_eq!(10.0, vec.get_y());
+    /// assert_eq!(-8.0, vec.get_z());
+    /// ```
+    pub fn scale(&self, factor: F) -> GenericVector<F> {
+        GenericVector {
+            x: self.x * factor,
+            y: self.y * factor,
+            z: self.z * factor,
+        }
+    }
+    /// Returns the dot product of the vector with another vector.
+    /// # Examples
+    /// ```rust
+    /// let vec = Vector::new(3.0, 5.0, -4.0);
+    /// let other_vec = Vector::new(-1.0, 2.0, 3.0);
+    /// assert_eq!(-5.0, vec.dot(&other_vec));
+    /// ```
+    pub fn dot(&self, other: &Self) -> F {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+    /// Returns the cross product of the vector with another vector (self × other).
+    /// # Examples
+    /// ```rust
+    /// let vec = Vector::new(1.0, 0.0, 0.0);
+    /// let other_vec = Vector::new(0.0, 1.0, 0.0);
+    /// assert_eq!(Vector::new(0.0, 0.0, 1.0), vec.cross(&other_vec));
+    /// ```
+    pub fn cross(&self, other: &Self) -> GenericVector<F> {
+        GenericVector {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+    /// Returns the scalar triple product self · (b × c), the signed volume of the parallelepiped
+    /// spanned by the three vectors. It is 0 if the vectors are coplanar and positive if they form
+    /// a right-handed system.
+    /// # Examples
+    /// ```rust
+    /// let a = Vector::new(2.0, 0.0, 0.0);
+    /// assert_eq!(24.0, a.scalar_triple(&Vector::new(0.0, 3.0, 0.0), &Vector::new(1.0, 1.0, 4.0)));
+    /// ```
+    pub fn scalar_triple(&self, b: &Self, c: &Self) -> F {
+        self.dot(&b.cross(c))
+    }
+    /// Returns the vector triple product self × (b × c), which equals `b (self · c) - c (self · b)`.
+    /// # Examples
+    /// ```rust
+    /// let a = Vector::new(1.0, 0.0, 0.0);
+    /// let product = a.vector_triple(&Vector::new(1.0, 1.0, 0.0), &Vector::new(0.0, 0.0, 1.0));
+    /// assert_eq!(Vector::new(0.0, 0.0, -1.0), product);
+    /// ```
+    pub fn vector_triple(&self, b: &Self, c: &Self) -> GenericVector<F> {
+        self.cross(&b.cross(c))
+    }
+    /// Returns the vector of the smaller components of self and other per axis.
+    /// # Examples
+    /// ```rust
+    /// let vec = Vector::new(1.0, 5.0, -2.0).component_min(&Vector::new(3.0, -1.0, 0.0));
+    /// assert_eq!(Vector::new(1.0, -1.0, -2.0), vec);
+    /// ```
+    pub fn component_min(&self, other: &Self) -> GenericVector<F> {
+        GenericVector::new(self.x.min(other.x), self.y.min(other.y), self.z.min(other.z))
+    }
+    /// Returns the vector of the larger components of self and other per axis.
+    /// # Examples
+    /// ```rust
+    /// let vec = Vector::new(1.0, 5.0, -2.0).component_max(&Vector::new(3.0, -1.0, 0.0));
+    /// assert_eq!(Vector::new(3.0, 5.0, 0.0), vec);
+    /// ```
+    pub fn component_max(&self, other: &Self) -> GenericVector<F> {
+        GenericVector::new(self.x.max(other.x), self.y.max(other.y), self.z.max(other.z))
+    }
+    /// Returns the magnitude (length) of the vector.
+    /// # Examples
+    /// ```rust
+    /// let vec = Vector::new(3.0, 4.0, 12.0);
+    /// assert_eq!(13.0, vec.magnitude());
+    /// ```
+    pub fn magnitude(&self) -> F {
+        self.dot(self).sqrt()
+    }
+    /// Returns a vector of length 1 pointing in the same direction, or None if the vector has no
+    /// length.
+    /// # Examples
+    /// ```rust
+    /// let vec = Vector::new(3.0, 0.0, -4.0).normalize().unwrap();
+    /// assert_eq!(Vector::new(0.6, 0.0, -0.8), vec);
+    /// ```
+    pub fn normalize(&self) -> Option<GenericVector<F>> {
+        let magnitude = self.magnitude();
+        if magnitude == F::ZERO || !magnitude.is_finite() {
+            return None;
+        }
+        Some(GenericVector {
+            x: self.x / magnitude,
+            y: self.y / magnitude,
+            z: self.z / magnitude,
+        })
+    }
+    /// Returns this east-north-up vector in the north-east-down convention used by many marine
+    /// systems: x and y are swapped and z is negated.
+    /// # Examples
+    /// ```rust
+    /// let ned = Vector::new(1.0, 2.0, -3.0).enu_to_ned();
+    /// assert_eq!(Vector::new(2.0, 1.0, 3.0), ned);
+    /// ```
+    pub fn enu_to_ned(&self) -> GenericVector<F> {
+        GenericVector { x: self.y, y: self.x, z: -self.z }
+    }
+    /// Returns this north-east-down vector in the east-north-up convention of this crate. This is
+    /// the inverse of [Vector::enu_to_ned].
+    /// # Examples
+    /// ```rust
+    /// let enu = Vector::new(2.0, 1.0, 3.0).ned_to_enu();
+    /// assert_eq!(Vector::new(1.0, 2.0, -3.0), enu);
+    /// ```
+    pub fn ned_to_enu(&self) -> GenericVector<F> {
+        GenericVector { x: self.y, y: self.x, z: -self.z }
+    }
+    /// Generates a new vector from a sonar contact report: the range, the bearing in rad measured
+    /// clockwise from north (the y axis) and the elevation in rad measured from the horizontal
+    /// plane, positive upwards. A depression angle is the negative elevation.
+    ///
+    /// This differs from the mathematical convention of [PolarVec]: phi is measured
+    /// counterclockwise from east, so `phi = pi/2 - bearing`, and theta is measured from the z
+    /// axis, so `theta = pi/2 - elevation`.
+    /// # Examples
+    /// ```rust
+    /// let vec = Vector::from_range_bearing_elevation(100.0, FRAC_PI_2, 0.0);
+    /// assert!((100.0 - vec.get_x()).abs() < F64_DELTA);
+    /// ```
+    pub fn from_range_bearing_elevation(range: F, bearing_rad: F, elevation_rad: F) -> GenericVector<F> {
+        let horizontal = range * elevation_rad.cos();
+        GenericVector {
+            x: horizontal * bearing_rad.sin(),
+            y: horizontal * bearing_rad.cos(),
+            z: range * elevation_rad.sin()
+        }
+    }
+    /// Returns the range, the bearing in rad and range 0..2*pi and the elevation in rad and range
+    /// -pi/2..pi/2 of the vector, as defined by [Vector::from_range_bearing_elevation]. A vertical
+    /// vector has bearing 0, the zero vector returns (0, 0, 0).
+    /// # Examples
+    /// ```rust
+    /// let (range, bearing, elevation) = Vector::new(0.0, -5.0, 0.0).to_range_bearing_elevation();
+    /// assert_eq!((5.0, PI, 0.0), (range, bearing, elevation));
+    /// ```
+    pub fn to_range_bearing_elevation(self) -> (F, F, F) {
+        let horizontal = (self.x * self.x + self.y * self.y).sqrt();
+        let bearing = self.x.atan2(self.y).rem_euclid(F::TAU);
+        let elevation = self.z.atan2(horizontal);
+        (self.magnitude(), bearing, elevation)
+    }
     /// Return a [PolarVector] representation of the vector.
     /// # Examples
     /// ```rust
@@ -184,56 +453,168 @@ impl Vector {
     /// assert!(abs_difference_phi < 0.00001);
     /// assert!(abs_difference_theta < 0.00001);
     /// ```
-    pub fn to_polar_vector(&self) -> PolarVec {
-        let r = (self.x.powi(2) + self.y.powi(2) + self.z.powi(2)).sqrt();
-        PolarVec {
-            r,
-            phi: self.y.atan2(self.x),
-            theta: (self.z / r).acos()
+    pub fn to_polar_vector(self) -> GenericPolarVec<F> {
+        let r = (self.x * self.x + self.y * self.y + self.z * self.z).sqrt();
+        GenericPolarVec::new(r, self.y.atan2(self.x), (self.z / r).acos())
+    }
+}
+
+/// Adds up all vectors of an iterator, starting from the zero vector.
+impl<F: Float> Sum for GenericVector<F> {
+    fn sum<I: Iterator<Item = GenericVector<F>>>(iter: I) -> GenericVector<F> {
+        iter.fold(GenericVector::new(F::ZERO, F::ZERO, F::ZERO), |sum, vec| sum.add(&vec))
+    }
+}
+
+/// Adds up all borrowed vectors of an iterator, starting from the zero vector.
+impl<'a, F: Float> Sum<&'a GenericVector<F>> for GenericVector<F> {
+    fn sum<I: Iterator<Item = &'a GenericVector<F>>>(iter: I) -> GenericVector<F> {
+        iter.copied().sum()
+    }
+}
+
+/// Converts a polar vector into the equivalent cartesian vector like [PolarVec::to_vector].
+impl<F: Float> From<GenericPolarVec<F>> for GenericVector<F> {
+    fn from(vec: GenericPolarVec<F>) -> GenericVector<F> {
+        vec.to_vector()
+    }
+}
+
+/// Converts a cartesian vector into the equivalent polar vector like [Vector::to_polar_vector].
+impl<F: Float> From<GenericVector<F>> for GenericPolarVec<F> {
+    fn from(vec: GenericVector<F>) -> GenericPolarVec<F> {
+        vec.to_polar_vector()
+    }
+}
+
+/// Like the world origin of [PolarVec], the constructors without arguments are only defined for
+/// double values.
+impl Vector {
+    /// Returns a vector containing the [world origin] (0,0,0).
+    /// # Examples
+    /// ```rust
+    /// let vec = Vector::get_world_origin();
+    /// assert_eq!(0.0, vec.x);
+    /// assert_eq!(0.0, vec.y);
+    /// assert_eq!(0.0, vec.z);
+    /// ```
+    ///
+    /// [world origin]: GetLinkLocation
+    pub fn get_world_origin() -> Vector {
+        let (x,y,z) = WORLD_ORIGIN;
+        Vector{x,y,z}
+    }
+    /// Returns the zero vector (0,0,0).
+    pub const fn zero() -> Vector {
+        Vector::new(0.0, 0.0, 0.0)
+    }
+    /// Returns the unit vector along the x axis (east).
+    pub const fn unit_x() -> Vector {
+        Vector::new(1.0, 0.0, 0.0)
+    }
+    /// Returns the unit vector along the y axis (north).
+    pub const fn unit_y() -> Vector {
+        Vector::new(0.0, 1.0, 0.0)
+    }
+    /// Returns the unit vector along the z axis (up).
+    /// # Examples
+    /// ```rust
+    /// assert_eq!(Vector::unit_z(), Vector::unit_x().cross(&Vector::unit_y()));
+    /// ```
+    pub const fn unit_z() -> Vector {
+        Vector::new(0.0, 0.0, 1.0)
+    }
+}
+
+
+/// Errors that can occur while validating the input of [PolarVec::try_new].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PolarError {
+    /// The radius or an angle is NaN or infinite.
+    NonFinite { r: f64, phi: f64, theta: f64 },
+    /// The azimut angle lies further outside of 0..2*pi than the tolerance allows.
+    PhiOutOfRange { phi: f64, tolerance: f64 },
+    /// The polar angle lies further outside of 0..pi than the tolerance allows.
+    ThetaOutOfRange { theta: f64, tolerance: f64 },
+}
+
+impl Display for PolarError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PolarError::NonFinite { r, phi, theta } => {
+                write!(f, "polar vector has non-finite values: r = {}, phi = {}, theta = {}", r, phi, theta)
+            }
+            PolarError::PhiOutOfRange { phi, tolerance } => {
+                write!(f, "azimut angle {} rad lies more than {} rad outside of 0..2*pi", phi, tolerance)
+            }
+            PolarError::ThetaOutOfRange { theta, tolerance } => {
+                write!(f, "polar angle {} rad lies more than {} rad outside of 0..pi", theta, tolerance)
+            }
         }
     }
 }
 
+impl Error for PolarError {}
+
+/// The angle in rad by which [PolarVec::try_new] lets phi and theta exceed their ranges. It
+/// accepts azimuts in -pi..pi but rejects angles given in degrees by mistake.
+pub const DEFAULT_ANGLE_TOLERANCE: f64 = PI;
 
-/// A vector in spherical coordinates from three double (```f64```) values.
+/// A vector in spherical coordinates from three floating point values of type ```F```.
 /// * **r** is the radius
 /// * **phi** is the azimut angle in rad and range 0..2*pi
 /// * **theta** is the polar angle in rad and range 0..pi
 #[derive(Debug, Copy, Clone)]
-pub struct PolarVec {
-    r: f64,
-    phi: f64,
-    theta: f64,
+pub struct GenericPolarVec<F: Float> {
+    r: F,
+    phi: F,
+    theta: F,
 }
 
-impl PartialEq for PolarVec {
-    /// Returns true if the difference between the values of self and other are smaller than [F64_DELTA].
+/// A polar vector of double (```f64```) values, the polar vector used throughout the crate.
+pub type PolarVec = GenericPolarVec<f64>;
+/// Another name for [PolarVec], to pair with [PolarVec32].
+pub type PolarVec64 = PolarVec;
+/// A polar vector of single (```f32```) values.
+pub type PolarVec32 = GenericPolarVec<f32>;
+
+impl<F: Float> PartialEq for GenericPolarVec<F> {
+    /// Returns true if the differences between the radii, the azimut angles and the polar angles
+    /// of self and other are all smaller than [F64_DELTA](crate::constants::F64_DELTA), so radii
+    /// that drift apart by rounding errors after arithmetic still compare equal. The tolerance is
+    /// the same for single values, so large ```f32``` radii only compare equal if they are equal.
+    ///
+    /// On the poles, i.e. if both polar angles lie within the tolerance of 0 or pi, the azimut
+    /// angle has no meaning and is ignored, since vectors that only got close to a pole by
+    /// rounding errors keep their azimut after canonicalization.
     /// # Examples
     /// ```rust
-    /// let vec_1 = PolarVec::new(10.0, PI, FACT_PI_2 + (F64_DELTA/2));
-    /// let vec_2 = PolarVec::new(10.0, PI, FACT_PI_2);
+    /// let vec_1 = PolarVec::new(10.0, PI, FRAC_PI_2 + (F64_DELTA/2));
+    /// let vec_2 = PolarVec::new(10.0, PI, FRAC_PI_2);
     /// assert_eq!(vec_1, vec_2);
+    ///
+    /// let vec_3 = PolarVec::new(10.0 + (F64_DELTA/2), PI, FRAC_PI_2);
+    /// assert_eq!(vec_3, vec_2);
     /// ```
     fn eq(&self, other: &Self) -> bool {
-        return {
-            equal_within_delta(self.r, other.r) &&
-                equal_within_delta(self.phi, other.phi) &&
-                equal_within_delta(self.theta, other.theta)
-        }
+        let at_pole = |theta: F| equal_within_delta(theta.to_f64(), 0.0) || equal_within_delta(theta.to_f64(), PI);
+        equal_within_delta(self.r.to_f64(), other.r.to_f64()) &&
+            equal_within_delta(self.theta.to_f64(), other.theta.to_f64()) &&
+            (equal_within_delta(self.phi.to_f64(), other.phi.to_f64()) || (at_pole(self.theta) && at_pole(other.theta)))
     }
 }
 
-impl Eq for PolarVec {}
+impl<F: Float> Eq for GenericPolarVec<F> {}
 
-impl PartialOrd for PolarVec {
+impl<F: Float> PartialOrd for GenericPolarVec<F> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(&other))
+        Some(self.cmp(other))
     }
 }
-impl Ord for PolarVec {
+impl<F: Float> Ord for GenericPolarVec<F> {
 
     fn cmp(&self, other: &Self) -> Ordering {
-        return if self.eq(&other) {
+        if self.eq(other) {
             Ordering::Equal
         } else if self.phi.ne(&other.phi) {
             if self.phi > other.phi {
@@ -251,22 +632,28 @@ impl Ord for PolarVec {
     }
 }
 
-impl Display for PolarVec {
+/// Displays the radius and the angles in degrees with the precision of the formatter, e.g.
+/// `format!("{:.1}", vec)`, or with [DEFAULT_DISPLAY_PRECISION] decimal places.
+impl<F: Float> Display for GenericPolarVec<F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "[Radius: {:?} m, Phi (azimut): {:?}°, Theta (polar): {:?}°]", self.r, self.phi, self.theta)
+        let precision = f.precision().unwrap_or(DEFAULT_DISPLAY_PRECISION);
+        let (phi, theta) = self.to_degrees();
+        write!(f, "[Radius: {:.*} m, Phi (azimut): {:.*}°, Theta (polar): {:.*}°]",
+               precision, self.r, precision, phi, precision, theta)
     }
 }
 
-impl PolarVec {
+impl<F: Float> GenericPolarVec<F> {
     /// Generates a new PolarVector. Phi and Theta are in radiance units.
     /// This Methode takes the input parameter and transform them into a unique expression. This
     /// guarantees that there is only one version of every possible vector.
     /// Following conventions are observed:
     /// 1. If r == 0: phi == theta == 0.
-    /// 2. If theta == 0: phi == 0.
+    /// 2. If theta == 0 or theta == pi: phi == 0.
     /// 3. r > 0
-    /// 4. phi is in range (0..2*pi]
-    /// 5. theta is in range (0..pi]
+    /// 4. phi is in range [0..2*pi)
+    /// 5. theta is in range [0..pi]
+    ///
     /// If the input parameters fall out these conventions, they are transformed to their equivalent
     /// expression that observes the convention.
     ///
@@ -293,95 +680,269 @@ impl PolarVec {
     /// let pol_vec2 = PolarVec::new(5.0, 0.1, FRAC_PI_2);
     /// assert_eq!(pol_vec1, pol_vec2);
     ///
-    /// let pol_vec1 = PolarVec::new(5.0, PI, PI);
-    /// let pol_vec2 = PolarVec::new(5.0, 0.0, 0.0);
+    /// let pol_vec1 = PolarVec::new(5.0, PI, -0.1);
+    /// let pol_vec2 = PolarVec::new(5.0, 0.0, 0.1);
+    /// assert_eq!(pol_vec1, pol_vec2);
+    ///
+    /// let pol_vec1 = PolarVec::new(5.0, 0.0, PI + 0.1);
+    /// let pol_vec2 = PolarVec::new(5.0, PI, PI - 0.1);
     /// assert_eq!(pol_vec1, pol_vec2);
     /// ```
     ///
-    pub fn new(r: f64, phi: f64, theta: f64) -> PolarVec {
-        let (r,phi,theta) = PolarVec::get_uni_coords(r, phi, theta);
-        PolarVec {r,phi,theta}
+    pub fn new(r: F, phi: F, theta: F) -> GenericPolarVec<F> {
+        let (r,phi,theta) = GenericPolarVec::get_uni_coords(r, phi, theta);
+        GenericPolarVec {r,phi,theta}
     }
 
-    /// Returns the vector based on [WORLD_ORIGIN], equal to calling
-    /// PolarVec::new(0.0, 0.0, 0.0);
+    /// Generates a new PolarVector like [PolarVec::new], but fails instead of canonicalizing
+    /// input that is likely a mistake: a non-finite radius or angle, or an angle that lies more
+    /// than [DEFAULT_ANGLE_TOLERANCE] outside of its range, such as an angle in degrees.
+    /// Use [PolarVec::try_new_with_tolerance] to choose the tolerance.
     /// # Examples
     /// ```rust
-    /// let pol_vec1 = PolarVec::get_world_origin();
-    /// let pol_vec2 = PolarVec::new(0.0, 0.0, 0.0)
-    /// assert_eq!(pol_vec1, pol_vec2);
+    /// assert_eq!(Ok(PolarVec::new(5.0, -FRAC_PI_2, FRAC_PI_2)), PolarVec::try_new(5.0, -FRAC_PI_2, FRAC_PI_2));
+    /// assert!(PolarVec::try_new(5.0, 90.0, 90.0).is_err());
     /// ```
-    /// [world origin]: GetLinkLocation
-    pub fn get_world_origin() -> PolarVec {
-        let (r,phi,theta) = WORLD_ORIGIN;
-        let (r,phi,theta) = PolarVec::get_uni_coords(r, phi, theta);
-        PolarVec {r,phi,theta}
+    pub fn try_new(r: F, phi: F, theta: F) -> Result<GenericPolarVec<F>, PolarError> {
+        GenericPolarVec::try_new_with_tolerance(r, phi, theta, F::from_f64(DEFAULT_ANGLE_TOLERANCE))
+    }
+    /// Generates a new PolarVector like [PolarVec::try_new], accepting phi in
+    /// `-tolerance..2*pi + tolerance` and theta in `-tolerance..pi + tolerance`. A tolerance of 0
+    /// only accepts angles that are already in range.
+    pub fn try_new_with_tolerance(r: F, phi: F, theta: F, tolerance: F) -> Result<GenericPolarVec<F>, PolarError> {
+        if !(r.is_finite() && phi.is_finite() && theta.is_finite()) {
+            return Err(PolarError::NonFinite { r: r.to_f64(), phi: phi.to_f64(), theta: theta.to_f64() });
+        }
+        if !(-tolerance..=F::TAU + tolerance).contains(&phi) {
+            return Err(PolarError::PhiOutOfRange { phi: phi.to_f64(), tolerance: tolerance.to_f64() });
+        }
+        if !(-tolerance..=F::PI + tolerance).contains(&theta) {
+            return Err(PolarError::ThetaOutOfRange { theta: theta.to_f64(), tolerance: tolerance.to_f64() });
+        }
+        Ok(GenericPolarVec::new(r, phi, theta))
+    }
+    /// Generates a new PolarVector from angles in degrees, observing the same conventions as
+    /// [PolarVec::new].
+    /// # Examples
+    /// ```rust
+    /// let pol_vec = PolarVec::from_degrees(5.0, 90.0, 90.0);
+    /// assert_eq!(PolarVec::new(5.0, FRAC_PI_2, FRAC_PI_2), pol_vec);
+    /// ```
+    pub fn from_degrees(r: F, phi_deg: F, theta_deg: F) -> GenericPolarVec<F> {
+        GenericPolarVec::new(r, phi_deg.to_radians(), theta_deg.to_radians())
     }
     /// Returns the radius of the polar vector
-    pub fn get_radius(&self) -> f64 {
+    pub fn get_radius(&self) -> F {
         self.r
     }
     /// Returns the azimut angle phi in rad
-    pub fn get_phi_in_rad(&self) -> f64 {
+    pub fn get_phi_in_rad(&self) -> F {
         self.phi
     }
     /// Returns the polar angle theta in rad
-    pub fn get_theta_in_rad(&self) -> f64 {
+    pub fn get_theta_in_rad(&self) -> F {
         self.theta
     }
+    /// Returns the azimut angle phi in degrees
+    pub fn get_phi_in_deg(&self) -> F {
+        self.phi.to_degrees()
+    }
+    /// Returns the polar angle theta in degrees
+    pub fn get_theta_in_deg(&self) -> F {
+        self.theta.to_degrees()
+    }
     /// Returns self.phi - other.phi
-    pub fn get_angle_difference_phi(&self, other: &Self) -> f64 {
+    pub fn get_angle_difference_phi(&self, other: &Self) -> F {
         other.phi - self.phi
     }
     /// Returns self.theta - other.theta
-    pub fn get_angle_difference_theta(&self, other: &Self) -> f64 {
+    pub fn get_angle_difference_theta(&self, other: &Self) -> F {
         other.theta - self.theta
     }
+    /// Returns a new vector with the azimut angle phi turned by delta_rad while keeping the
+    /// radius and the polar angle. The result is brought back into range (0..2*pi].
+    /// # Examples
+    /// ```rust
+    /// let pol_vec = PolarVec::new(10.0, FRAC_PI_2, FRAC_PI_2);
+    /// let rotated = pol_vec.rotate_azimuth(PI);
+    /// assert_eq!(PolarVec::new(10.0, PI + FRAC_PI_2, FRAC_PI_2), rotated);
+    /// ```
+    pub fn rotate_azimuth(&self, delta_rad: F) -> GenericPolarVec<F> {
+        GenericPolarVec::new(self.r, self.phi + delta_rad, self.theta)
+    }
+    /// Returns a new vector with the polar angle theta turned by delta_rad while keeping the
+    /// radius. The rotation happens in the plane spanned by the vector and the z axis.
+    ///
+    /// If the rotation crosses one of the poles, the vector continues on the opposite side of the
+    /// sphere: theta is mirrored back into range [0..pi] and phi is turned by pi. Turning
+    /// (r, 0, 0.1) by -0.2 therefore results in (r, pi, 0.1).
+    /// # Examples
+    /// ```rust
+    /// let pol_vec = PolarVec::new(10.0, 0.0, 0.1);
+    /// let rotated = pol_vec.rotate_polar(-0.2);
+    /// assert_eq!(PolarVec::new(10.0, PI, 0.1), rotated);
+    /// ```
+    pub fn rotate_polar(&self, delta_rad: F) -> GenericPolarVec<F> {
+        let mut phi = self.phi;
+        let mut theta = (self.theta + delta_rad).rem_euclid(F::TAU);
+        if theta > F::PI {
+            theta = F::TAU - theta;
+            phi += F::PI;
+        }
+        GenericPolarVec::new(self.r, phi, theta)
+    }
+    /// Returns a new vector with the radius multiplied by factor while keeping the direction.
+    /// A negative factor flips the vector through the origin onto the antipodal direction.
+    /// # Examples
+    /// ```rust
+    /// let pol_vec = PolarVec::new(10.0, FRAC_PI_2, FRAC_PI_4);
+    /// assert_eq!(PolarVec::new(20.0, FRAC_PI_2, FRAC_PI_4), pol_vec.scale_radius(2.0));
+    /// assert_eq!(PolarVec::new(10.0, PI + FRAC_PI_2, PI - FRAC_PI_4), pol_vec.scale_radius(-1.0));
+    /// ```
+    pub fn scale_radius(&self, factor: F) -> GenericPolarVec<F> {
+        GenericPolarVec::new(self.r * factor, self.phi, self.theta)
+    }
+    /// Returns the azimut angle phi and the polar angle theta converted into degrees.
+    /// # Examples
+    /// ```rust
+    /// let pol_vec = PolarVec::new(10.0, PI, FRAC_PI_2);
+    /// let (phi, theta) = pol_vec.to_degrees();
+    /// assert_eq!(180.0, phi);
+    /// assert_eq!(90.0, theta);
+    /// ```
+    pub fn to_degrees(self) -> (F, F) {
+        (self.phi.to_degrees(), self.theta.to_degrees())
+    }
     /// Returns a equivalent cartesian [Vector]
     /// [Vector]: GetLinkLocation
-    pub fn to_vector(&self) -> Vector {
-        Vector {
+    pub fn to_vector(self) -> GenericVector<F> {
+        GenericVector {
             x: self.r * self.phi.cos() * self.theta.sin(),
             y: self.r * self.phi.sin() * self.theta.sin(),
             z: self.r * self.theta.cos()
         }
     }
 
-    fn get_uni_coords(mut r: f64, mut phi: f64, mut theta: f64) -> (f64,f64,f64) {
+    fn get_uni_coords(mut r: F, mut phi: F, mut theta: F) -> (F,F,F) {
 
-        if phi < 0.0 || phi >= TAU {
-            phi = phi.rem_euclid(TAU);
-        }
-        if theta < 0.0 || theta >= PI {
-            theta = theta.rem_euclid(PI);
-            phi = (phi + PI) % TAU;
+        if r < F::ZERO {
+            r = r.abs();
+            phi += F::PI;
+            theta = F::PI - theta;
         }
-
-        if r == 0.0 {
-            phi = 0.0;
-            theta = 0.0;
+        // A polar angle beyond a pole continues on the opposite side of the sphere.
+        if !(F::ZERO..=F::PI).contains(&theta) {
+            theta = theta.rem_euclid(F::TAU);
+            if theta > F::PI {
+                theta = F::TAU - theta;
+                phi += F::PI;
+            }
         }
-        else if theta == 0.0 {
-            phi = 0.0;
+        if !(F::ZERO..F::TAU).contains(&phi) {
+            phi = phi.rem_euclid(F::TAU);
         }
 
-        if r < 0.0 {
-            r = r.abs();
-            phi = (phi + PI) % TAU;
-            theta = PI - theta;
+        if r == F::ZERO {
+            phi = F::ZERO;
+            theta = F::ZERO;
+        }
+        else if theta == F::ZERO || theta == F::PI {
+            phi = F::ZERO;
         }
 
         (r,phi,theta)
     }
 }
 
+/// Like for [Vector], the world origin is only defined for double values.
+impl PolarVec {
+    /// Returns the vector based on [WORLD_ORIGIN], equal to calling
+    /// PolarVec::new(0.0, 0.0, 0.0);
+    /// # Examples
+    /// ```rust
+    /// let pol_vec1 = PolarVec::get_world_origin();
+    /// let pol_vec2 = PolarVec::new(0.0, 0.0, 0.0)
+    /// assert_eq!(pol_vec1, pol_vec2);
+    /// ```
+    /// [world origin]: GetLinkLocation
+    pub fn get_world_origin() -> PolarVec {
+        let (r,phi,theta) = WORLD_ORIGIN;
+        let (r,phi,theta) = PolarVec::get_uni_coords(r, phi, theta);
+        PolarVec {r,phi,theta}
+    }
+}
+
+/// Returns the centroid (the mean position) of the points, or None if there are no points.
+/// # Examples
+/// ```rust
+/// let points = [Vector::new(0.0, 0.0, 0.0), Vector::new(4.0, 2.0, -6.0)];
+/// assert_eq!(Some(Vector::new(2.0, 1.0, -3.0)), centroid(&points));
+/// ```
+pub fn centroid(points: &[Vector]) -> Option<Vector> {
+    if points.is_empty() {
+        return None;
+    }
+    let sum: Vector = points.iter().sum();
+    Some(sum.scale(1.0 / points.len() as f64))
+}
+
+/// Returns the smallest axis aligned box containing all points as its (min corner, max corner),
+/// or None if there are no points.
+/// # Examples
+/// ```rust
+/// let points = [Vector::new(1.0, 5.0, -2.0), Vector::new(3.0, -1.0, 0.0)];
+/// assert_eq!(Some((Vector::new(1.0, -1.0, -2.0), Vector::new(3.0, 5.0, 0.0))), bounding_box(&points));
+/// ```
+pub fn bounding_box(points: &[Vector]) -> Option<(Vector, Vector)> {
+    let first = points.first()?;
+    Some(points[1..].iter().fold((*first, *first), |(min, max), point| {
+        (min.component_min(point), max.component_max(point))
+    }))
+}
+
+/// The edge length in m of the grid the components are rounded to by [cloud_fingerprint].
+pub const FINGERPRINT_QUANTUM: f64 = 0.001;
+
+/// Returns a fingerprint of a point cloud that changes when the cloud changes materially, e.g. to
+/// detect that cached results derived from the cloud are outdated.
+///
+/// Every component is quantized by rounding it to the nearest multiple of [FINGERPRINT_QUANTUM]
+/// before it is hashed, so noise well below the quantum does not change the fingerprint. Values
+/// that lie almost exactly between two multiples can still round differently. The fingerprint
+/// depends on the order of the points and is the same on every run and platform (64 bit FNV-1a
+/// over the little endian bytes of the quantized components).
+/// # Examples
+/// ```rust
+/// let cloud = [Vector::new(1.0, 2.0, 3.0)];
+/// let noisy = [Vector::new(1.0000001, 2.0, 3.0)];
+/// assert_eq!(cloud_fingerprint(&cloud), cloud_fingerprint(&noisy));
+/// ```
+pub fn cloud_fingerprint(points: &[Vector]) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for point in points {
+        for component in [point.x, point.y, point.z].iter() {
+            let quantized = (component / FINGERPRINT_QUANTUM).round() as i64;
+            for byte in quantized.to_le_bytes().iter() {
+                hash ^= u64::from(*byte);
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        }
+    }
+    hash
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     mod vector {
         use crate::physics::vectors::Vector;
-        use std::f64::consts::FRAC_PI_2;
+        use crate::utils::helper_functions::equal_within_delta;
+        use std::cmp::Ordering;
+        use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
 
         #[test]
         fn creation() {
@@ -418,6 +979,244 @@ mod tests {
             assert_eq!(d,c);
         }
 
+        #[test]
+        fn scale() {
+            let a = Vector::new(10.0, 5.0, -6.0);
+            assert_eq!(Vector::new(20.0, 10.0, -12.0), a.scale(2.0));
+            assert_eq!(Vector::new(-5.0, -2.5, 3.0), a.scale(-0.5));
+        }
+
+        #[test]
+        fn dot() {
+            let a = Vector::new(3.0, 5.0, -4.0);
+            let b = Vector::new(-1.0, 2.0, 3.0);
+            assert_eq!(-5.0, a.dot(&b));
+            assert_eq!(0.0, Vector::new(1.0, 0.0, 0.0).dot(&Vector::new(0.0, 1.0, 0.0)));
+        }
+
+        #[test]
+        fn cross() {
+            let a = Vector::new(3.0, 5.0, -4.0);
+            let b = Vector::new(-1.0, 2.0, 3.0);
+            let c = a.cross(&b);
+            assert_eq!(Vector::new(23.0, -5.0, 11.0), c);
+            assert_eq!(0.0, c.dot(&a));
+            assert_eq!(0.0, c.dot(&b));
+            assert_eq!(Vector::new(-23.0, 5.0, -11.0), b.cross(&a));
+        }
+
+        #[test]
+        fn scalar_triple() {
+            // Three contacts in the same plane through the origin span no volume.
+            let a = Vector::new(1.0, 2.0, 3.0);
+            let b = Vector::new(-2.0, 0.5, 1.0);
+            let c = a.scale(2.0).add(&b.scale(-3.0));
+            assert!(a.scalar_triple(&b, &c).abs() < 1e-12);
+
+            // A box of 2 x 3 x 4 m.
+            let x = Vector::new(2.0, 0.0, 0.0);
+            let y = Vector::new(0.0, 3.0, 0.0);
+            let z = Vector::new(0.0, 0.0, 4.0);
+            assert_eq!(24.0, x.scalar_triple(&y, &z));
+            assert_eq!(24.0, y.scalar_triple(&z, &x));
+            assert_eq!(-24.0, x.scalar_triple(&z, &y));
+            // Shearing the box keeps its volume.
+            assert_eq!(24.0, x.scalar_triple(&y.add(&x), &z.add(&y)));
+        }
+
+        #[test]
+        fn vector_triple() {
+            let a = Vector::new(1.0, 2.0, 3.0);
+            let b = Vector::new(-2.0, 0.5, 1.0);
+            let c = Vector::new(4.0, -1.0, 0.5);
+            let expected = b.scale(a.dot(&c)).sub(&c.scale(a.dot(&b)));
+            assert_eq!(expected, a.vector_triple(&b, &c));
+            assert_eq!(Vector::new(0.0, 0.0, 0.0), a.vector_triple(&b, &b));
+        }
+
+        #[test]
+        fn magnitude() {
+            assert_eq!(13.0, Vector::new(3.0, 4.0, 12.0).magnitude());
+            assert_eq!(13.0, Vector::new(-3.0, -4.0, -12.0).magnitude());
+            assert_eq!(0.0, Vector::get_world_origin().magnitude());
+        }
+
+        #[test]
+        fn normalize() {
+            assert_eq!(Some(Vector::new(0.6, 0.0, -0.8)), Vector::new(3.0, 0.0, -4.0).normalize());
+            assert_eq!(Some(Vector::new(0.0, 1.0, 0.0)), Vector::new(0.0, 0.5, 0.0).normalize());
+            assert_eq!(None, Vector::get_world_origin().normalize());
+        }
+
+        #[test]
+        fn zero_and_units() {
+            assert_eq!(Vector::get_world_origin(), Vector::zero());
+            assert_eq!(Vector::unit_z(), Vector::unit_x().cross(&Vector::unit_y()));
+            assert_eq!(Vector::unit_x(), Vector::unit_y().cross(&Vector::unit_z()));
+            assert_eq!(Vector::unit_y(), Vector::unit_z().cross(&Vector::unit_x()));
+            for unit in [Vector::unit_x(), Vector::unit_y(), Vector::unit_z()].iter() {
+                assert_eq!(1.0, unit.magnitude());
+                assert_eq!(*unit, unit.add(&Vector::zero()));
+            }
+        }
+
+        #[test]
+        fn index() {
+            let mut vec = Vector::new(3.0, -5.0, 7.5);
+            assert_eq!(3.0, vec[0]);
+            assert_eq!(-5.0, vec[1]);
+            assert_eq!(7.5, vec[2]);
+
+            vec[1] = 2.0;
+            vec[2] += 0.5;
+            assert_eq!(Vector::new(3.0, 2.0, 8.0), vec);
+
+            // Loop over the axes, e.g. for a bounding box.
+            let points = [Vector::new(1.0, 5.0, -2.0), Vector::new(4.0, -1.0, 0.0)];
+            let mut max = points[0];
+            for point in points.iter() {
+                for axis in 0..3 {
+                    max[axis] = max[axis].max(point[axis]);
+                }
+            }
+            assert_eq!(Vector::new(4.0, 5.0, 0.0), max);
+        }
+
+        #[test]
+        #[should_panic(expected = "index out of bounds: the len is 3 but the index is 3")]
+        fn index_out_of_range() {
+            let vec = Vector::new(1.0, 2.0, 3.0);
+            let _ = vec[3];
+        }
+
+        #[test]
+        fn ordering() {
+            let a = Vector::new(1.0, 2.0, 3.0);
+            assert_eq!(Ordering::Equal, a.cmp(&Vector::new(1.0, 2.0, 3.0)));
+            assert_eq!(Ordering::Less, a.cmp(&Vector::new(2.0, 0.0, 0.0)));
+            assert_eq!(Ordering::Less, a.cmp(&Vector::new(1.0, 3.0, 0.0)));
+            assert_eq!(Ordering::Greater, a.cmp(&Vector::new(1.0, 2.0, -3.0)));
+            assert!(a < Vector::new(1.0, 2.0, 4.0));
+            assert_eq!(Ordering::Equal, Vector::new(0.0, -0.0, 0.0).cmp(&Vector::new(-0.0, 0.0, 0.0)));
+        }
+
+        #[test]
+        fn sort_with_nan() {
+            let mut vectors = vec![
+                Vector::new(f64::NAN, 0.0, 0.0),
+                Vector::new(1.0, 5.0, 0.0),
+                Vector::new(1.0, f64::NAN, 0.0),
+                Vector::new(-3.0, 0.0, 0.0),
+                Vector::new(1.0, 5.0, f64::NAN),
+                Vector::new(f64::NEG_INFINITY, 1.0, 1.0),
+                Vector::new(1.0, -5.0, 0.0),
+            ];
+            vectors.sort();
+
+            let finite: Vec<Vector> = vectors.iter().copied()
+                .filter(|v| !(v.get_x().is_nan() || v.get_y().is_nan() || v.get_z().is_nan()))
+                .collect();
+            assert_eq!(vec![Vector::new(f64::NEG_INFINITY, 1.0, 1.0), Vector::new(-3.0, 0.0, 0.0),
+                            Vector::new(1.0, -5.0, 0.0), Vector::new(1.0, 5.0, 0.0)], finite);
+            assert!(vectors[4].get_z().is_nan());
+            assert!(vectors[5].get_y().is_nan());
+            assert!(vectors[6].get_x().is_nan());
+
+            // The order stays consistent, so sorting again changes nothing.
+            let bits = |vectors: &[Vector]| -> Vec<[u64; 3]> {
+                vectors.iter().map(|v| [v.get_x().to_bits(), v.get_y().to_bits(), v.get_z().to_bits()]).collect()
+            };
+            let sorted = bits(&vectors);
+            vectors.reverse();
+            vectors.sort();
+            assert_eq!(sorted, bits(&vectors));
+        }
+
+        #[test]
+        fn display_precision() {
+            let vec = Vector::new(1.23456, -20.0, 0.0005);
+            assert_eq!("[X: 1.235 m, Y: -20.000 m, Z: 0.001 m]", format!("{}", vec));
+            assert_eq!("[X: 1.2 m, Y: -20.0 m, Z: 0.0 m]", format!("{:.1}", vec));
+            assert_eq!("[X: 1.235 m, Y: -20.000 m, Z: 0.001 m]", format!("{:.3}", vec));
+            assert_eq!("[X: 1.23456 m, Y: -20.00000 m, Z: 0.00050 m]", format!("{:.5}", vec));
+        }
+
+        #[test]
+        fn hash_set() {
+            use std::collections::HashSet;
+
+            let mut set = HashSet::new();
+            assert!(set.insert(Vector::new(1.0, 2.0, 3.0)));
+            assert!(set.insert(Vector::new(-1.0, 2.0, 3.0)));
+            assert!(set.insert(Vector::new(3.0, 2.0, 1.0)));
+            assert!(!set.insert(Vector::new(1.0, 2.0, 3.0)));
+            assert_eq!(3, set.len());
+
+            assert!(set.contains(&Vector::new(1.0, 2.0, 3.0)));
+            assert!(set.contains(&Vector::new(1.0, 1.0, 1.0).add(&Vector::new(0.0, 1.0, 2.0))));
+            assert!(!set.contains(&Vector::new(1.0, 2.0, 3.5)));
+
+            // 0.0 and -0.0 are equal and hence the same key.
+            assert!(set.insert(Vector::new(0.0, 0.0, 0.0)));
+            assert!(set.contains(&Vector::new(-0.0, 0.0, -0.0)));
+            assert!(!set.insert(Vector::new(-0.0, -0.0, -0.0)));
+        }
+
+        #[test]
+        fn enu_to_ned() {
+            // A contact 100 m east, 50 m north and 20 m below the surface.
+            let enu = Vector::new(100.0, 50.0, -20.0);
+            assert_eq!(Vector::new(50.0, 100.0, 20.0), enu.enu_to_ned());
+            assert_eq!(Vector::new(0.0, 0.0, 1.0), Vector::new(0.0, 0.0, -1.0).enu_to_ned());
+        }
+
+        #[test]
+        fn ned_round_trip() {
+            let vec = Vector::new(-3.5, 7.25, 12.0);
+            assert_eq!(vec, vec.enu_to_ned().ned_to_enu());
+            assert_eq!(vec, vec.ned_to_enu().enu_to_ned());
+            assert_eq!(vec.magnitude(), vec.enu_to_ned().magnitude());
+        }
+
+        #[test]
+        fn sum() {
+            let vecs = [Vector::new(1.0, 2.0, 3.0), Vector::new(-4.0, 0.5, 10.0), Vector::new(0.25, -8.0, -1.0)];
+            let expected = vecs.iter().fold(Vector::zero(), |sum, vec| sum.add(vec));
+            assert_eq!(Vector::new(-2.75, -5.5, 12.0), expected);
+            assert_eq!(expected, vecs.iter().copied().sum::<Vector>());
+            assert_eq!(expected, vecs.iter().sum::<Vector>());
+            assert_eq!(Vector::zero(), Vec::<Vector>::new().into_iter().sum::<Vector>());
+        }
+
+        #[test]
+        fn range_bearing_elevation_axes() {
+            let north = Vector::from_range_bearing_elevation(10.0, 0.0, 0.0);
+            assert_eq!(Vector::new(0.0, 10.0, 0.0), north);
+            let east = Vector::from_range_bearing_elevation(10.0, FRAC_PI_2, 0.0);
+            assert!(equal_within_delta(10.0, east.get_x()));
+            assert!(equal_within_delta(0.0, east.get_y()));
+            // A depression angle points below the horizontal plane.
+            let down = Vector::from_range_bearing_elevation(10.0, 0.0, -FRAC_PI_2);
+            assert!(equal_within_delta(-10.0, down.get_z()));
+            assert!(equal_within_delta(0.0, down.get_y()));
+
+            assert_eq!((0.0, 0.0, 0.0), Vector::get_world_origin().to_range_bearing_elevation());
+            assert_eq!((3.0, 0.0, FRAC_PI_2), Vector::new(0.0, 0.0, 3.0).to_range_bearing_elevation());
+        }
+
+        #[test]
+        fn range_bearing_elevation_round_trip() {
+            for bearing in [0.0, FRAC_PI_4, FRAC_PI_2, 2.5, PI, 4.0, 1.5 * PI, 6.0].iter() {
+                for elevation in [-1.2, -0.3, 0.0, 0.7].iter() {
+                    let vec = Vector::from_range_bearing_elevation(250.0, *bearing, *elevation);
+                    let (range, round_bearing, round_elevation) = vec.to_range_bearing_elevation();
+                    assert!(equal_within_delta(250.0, range));
+                    assert!(equal_within_delta(*bearing, round_bearing), "{} != {}", bearing, round_bearing);
+                    assert!(equal_within_delta(*elevation, round_elevation));
+                }
+            }
+        }
+
         #[test]
         fn to_polar_vector() {
             let a = Vector::new(10.0, 0.0, 0.0);
@@ -432,7 +1231,7 @@ mod tests {
 
     mod polar_vector {
         use super::*;
-        use std::f64::consts::{FRAC_PI_8, FRAC_PI_4};
+        use std::f64::consts::{FRAC_PI_2, FRAC_PI_8, FRAC_PI_4, TAU};
 
         #[test]
         fn test_new_vec(){
@@ -457,19 +1256,20 @@ mod tests {
             assert_eq!(a,b);
 
             let a = PolarVec::new(5.0, FRAC_PI_8, PI);
-            let b = PolarVec::new(5.0, 0.0, 0.0);
+            let b = PolarVec::new(5.0, 0.0, PI);
             assert_eq!(a,b);
+            assert_ne!(a, PolarVec::new(5.0, 0.0, 0.0));
 
             let a = PolarVec::new(5.0, FRAC_PI_8, - FRAC_PI_4);
-            let b = PolarVec::new(5.0, FRAC_PI_8 + PI, PI - FRAC_PI_4);
+            let b = PolarVec::new(5.0, FRAC_PI_8 + PI, FRAC_PI_4);
             assert_eq!(a,b);
 
             let a = PolarVec::new(5.0, FRAC_PI_8, PI + FRAC_PI_4);
-            let b = PolarVec::new(5.0, FRAC_PI_8 + PI, FRAC_PI_4);
+            let b = PolarVec::new(5.0, FRAC_PI_8 + PI, PI - FRAC_PI_4);
             assert_eq!(a,b);
 
             let a = PolarVec::new(5.0, FRAC_PI_8 + PI, PI + FRAC_PI_4);
-            let b = PolarVec::new(5.0, FRAC_PI_8, FRAC_PI_4);
+            let b = PolarVec::new(5.0, FRAC_PI_8, PI - FRAC_PI_4);
             assert_eq!(a,b);
 
             let a = PolarVec::new(-5.0, PI, FRAC_PI_2);
@@ -507,14 +1307,57 @@ mod tests {
 
         #[test]
         fn get_angle_difference(){
-            let a = PolarVec::new(1.0, 20.0, 10.0);
-            let b = PolarVec::new(1.0, 40.0, 160.0);
+            let a = PolarVec::new(1.0, 20.0_f64.to_radians(), 10.0_f64.to_radians());
+            let b = PolarVec::new(1.0, 40.0_f64.to_radians(), 160.0_f64.to_radians());
+
+            assert!(equal_within_delta(-20.0_f64.to_radians(), b.get_angle_difference_phi(&a)));
+            assert!(equal_within_delta(20.0_f64.to_radians(), a.get_angle_difference_phi(&b)));
+            assert!(equal_within_delta(150.0_f64.to_radians(), a.get_angle_difference_theta(&b)));
+            assert!(equal_within_delta(- 150.0_f64.to_radians(), b.get_angle_difference_theta(&a)));
+
+        }
+
+        #[test]
+        fn rotate_azimuth(){
+            let a = PolarVec::new(10.0, FRAC_PI_8, FRAC_PI_4);
+            assert_eq!(a, a.rotate_azimuth(TAU));
+            assert_eq!(a, a.rotate_azimuth(-TAU));
+            assert_eq!(PolarVec::new(10.0, FRAC_PI_8 + FRAC_PI_2, FRAC_PI_4), a.rotate_azimuth(FRAC_PI_2));
+            assert_eq!(PolarVec::new(10.0, TAU - FRAC_PI_8, FRAC_PI_4), a.rotate_azimuth(- FRAC_PI_4));
+        }
+
+        #[test]
+        fn rotate_polar(){
+            let a = PolarVec::new(10.0, FRAC_PI_8, FRAC_PI_4);
+            assert_eq!(a, a.rotate_polar(TAU));
+            assert_eq!(PolarVec::new(10.0, FRAC_PI_8, FRAC_PI_2), a.rotate_polar(FRAC_PI_4));
+
+            let a = PolarVec::new(10.0, 0.0, 0.1);
+            assert_eq!(PolarVec::new(10.0, PI, 0.1), a.rotate_polar(-0.2));
 
-            assert_eq!(-20.0, b.get_angle_difference_phi(&a));
-            assert_eq!(20.0, a.get_angle_difference_phi(&b));
-            assert_eq!(150.0, a.get_angle_difference_theta(&b));
-            assert_eq!(- 150.0, b.get_angle_difference_theta(&a));
+            let a = PolarVec::new(10.0, FRAC_PI_2, PI - 0.1);
+            assert_eq!(PolarVec::new(10.0, PI + FRAC_PI_2, PI - 0.1), a.rotate_polar(0.2));
+        }
 
+        #[test]
+        fn scale_radius(){
+            let a = PolarVec::new(10.0, FRAC_PI_8, FRAC_PI_4);
+            assert_eq!(PolarVec::new(20.0, FRAC_PI_8, FRAC_PI_4), a.scale_radius(2.0));
+            assert_eq!(PolarVec::new(5.0, FRAC_PI_8, FRAC_PI_4), a.scale_radius(0.5));
+            assert_eq!(PolarVec::get_world_origin(), a.scale_radius(0.0));
+        }
+
+        #[test]
+        fn scale_radius_negative(){
+            let a = PolarVec::new(10.0, FRAC_PI_8, FRAC_PI_4);
+            let b = a.scale_radius(-2.0);
+            assert_eq!(PolarVec::new(20.0, PI + FRAC_PI_8, PI - FRAC_PI_4), b);
+
+            let vec_a = a.to_vector();
+            let vec_b = b.to_vector();
+            assert!(equal_within_delta(-2.0 * vec_a.get_x(), vec_b.get_x()));
+            assert!(equal_within_delta(-2.0 * vec_a.get_y(), vec_b.get_y()));
+            assert!(equal_within_delta(-2.0 * vec_a.get_z(), vec_b.get_z()));
         }
 
         #[test]
@@ -533,6 +1376,75 @@ mod tests {
             assert_ne!(a,c);
         }
         #[test]
+        fn partial_eq_radius_tolerance() {
+            use crate::constants::F64_DELTA;
+
+            let a = PolarVec::new(10.0, 1.0, 1.0);
+            assert_eq!(a, PolarVec::new(10.0 + F64_DELTA / 2.0, 1.0, 1.0));
+            assert_eq!(a, PolarVec::new(10.0 - F64_DELTA / 2.0, 1.0, 1.0));
+            assert_ne!(a, PolarVec::new(10.0 + 2.0 * F64_DELTA, 1.0, 1.0));
+
+            // The radius after a round trip through cartesian coordinates drifts by rounding errors.
+            let round_trip = a.to_vector().to_polar_vector();
+            assert_eq!(a, round_trip);
+            assert_eq!(a.scale_radius(0.1).scale_radius(10.0), a);
+        }
+        #[test]
+        fn conversion_with_into() {
+            let polar = PolarVec::new(250.0, 2.0, 1.2);
+            let vec: Vector = polar.into();
+            assert_eq!(polar.to_vector(), vec);
+            let round_trip: PolarVec = vec.into();
+            assert_eq!(polar, round_trip);
+
+            let vec = Vector::new(-30.0, 40.0, -120.0);
+            let polar = PolarVec::from(vec);
+            assert!(equal_within_delta(130.0, polar.get_radius()));
+            let round_trip = Vector::from(polar);
+            assert!(equal_within_delta(vec.get_x(), round_trip.get_x()));
+            assert!(equal_within_delta(vec.get_y(), round_trip.get_y()));
+            assert!(equal_within_delta(vec.get_z(), round_trip.get_z()));
+        }
+        #[test]
+        fn conversion_keeps_phi_in_range() {
+            let polar = PolarVec::from(Vector::new(0.0, -10.0, 0.0));
+            assert!(equal_within_delta(PI + FRAC_PI_2, polar.get_phi_in_rad()));
+            assert!(equal_within_delta(FRAC_PI_2, polar.get_theta_in_rad()));
+
+            let vec = Vector::new(20.0, -35.0, 8.0);
+            let polar = PolarVec::from(vec);
+            assert!((0.0..TAU).contains(&polar.get_phi_in_rad()));
+            let round_trip = Vector::from(polar);
+            assert!(equal_within_delta(vec.get_x(), round_trip.get_x()));
+            assert!(equal_within_delta(vec.get_y(), round_trip.get_y()));
+            assert!(equal_within_delta(vec.get_z(), round_trip.get_z()));
+
+            let down = Vector::new(0.0, 0.0, -1.0);
+            let polar = down.to_polar_vector();
+            assert!(equal_within_delta(PI, polar.get_theta_in_rad()));
+            assert_vec_eq(&down, &polar.to_vector());
+            assert_vec_eq(&down, &Vector::from(PolarVec::from(down)));
+
+            assert_eq!(PolarVec::new(0.0, 0.0, 0.0), Vector::zero().to_polar_vector());
+            assert_eq!(0.0, Vector::zero().to_polar_vector().get_theta_in_rad());
+        }
+        #[test]
+        fn partial_eq_at_poles() {
+            // Slightly off the pole the azimut survives canonicalization, but has no meaning.
+            let north = PolarVec::new(5.0, 1.0, 1e-8);
+            assert_eq!(1.0, north.get_phi_in_rad());
+            assert_eq!(north, PolarVec::new(5.0, 2.5, 0.0));
+            assert_eq!(north, PolarVec::new(5.0, 4.0, 2e-8));
+            assert_eq!(north.cmp(&PolarVec::new(5.0, 2.5, 0.0)), std::cmp::Ordering::Equal);
+
+            let south = PolarVec::new(5.0, 1.0, PI - 1e-8);
+            assert_eq!(south, PolarVec::new(5.0, 3.0, PI - 1e-8));
+
+            // Away from the poles the azimut still counts, as does the hemisphere.
+            assert_ne!(PolarVec::new(5.0, 1.0, 0.1), PolarVec::new(5.0, 2.5, 0.1));
+            assert_ne!(north, south);
+        }
+        #[test]
         fn test_total_ordering(){
             let a = PolarVec::new(1.0, 1.0, 1.0);
             let b = PolarVec::new(1.0, 1.0, 1.0);
@@ -575,8 +1487,199 @@ mod tests {
         #[test]
         fn test_display(){
             let a = PolarVec::new(1.0, 1.0, 1.0);
-            println!("{}", a)
+            println!("{}", a);
+
+            let a = PolarVec::new(10.0, PI, FRAC_PI_2);
+            assert_eq!("[Radius: 10.000 m, Phi (azimut): 180.000°, Theta (polar): 90.000°]", format!("{}", a));
+        }
+
+        #[test]
+        fn display_precision(){
+            let a = PolarVec::new(12.3456, 1.0, 0.5);
+            assert_eq!("[Radius: 12.3 m, Phi (azimut): 57.3°, Theta (polar): 28.6°]", format!("{:.1}", a));
+            assert_eq!("[Radius: 12.346 m, Phi (azimut): 57.296°, Theta (polar): 28.648°]", format!("{:.3}", a));
+            assert_eq!("[Radius: 12 m, Phi (azimut): 57°, Theta (polar): 29°]", format!("{:.0}", a));
+        }
+
+        #[test]
+        fn try_new_valid(){
+            assert_eq!(Ok(PolarVec::new(5.0, 1.0, 2.0)), PolarVec::try_new(5.0, 1.0, 2.0));
+            assert_eq!(Ok(PolarVec::new(5.0, PI, 0.5)), PolarVec::try_new(5.0, -PI, 0.5));
+            assert_eq!(Ok(PolarVec::new(5.0, 0.0, PI)), PolarVec::try_new_with_tolerance(5.0, TAU, PI, 0.0));
+        }
+
+        #[test]
+        fn try_new_keeps_direction(){
+            // Accepted polar angles beyond a pole continue on the opposite side of the sphere.
+            let down = PolarVec::try_new(1.0, 0.0, PI).unwrap().to_vector();
+            assert_vec_eq(&Vector::new(0.0, 0.0, -1.0), &down);
+
+            let past_south_pole = PolarVec::try_new(1.0, 0.0, PI + 0.1).unwrap().to_vector();
+            assert_vec_eq(&Vector::new(-(0.1_f64.sin()), 0.0, -(0.1_f64.cos())), &past_south_pole);
+
+            let past_north_pole = PolarVec::try_new(1.0, 0.0, -0.1).unwrap().to_vector();
+            assert_vec_eq(&Vector::new(-(0.1_f64.sin()), 0.0, 0.1_f64.cos()), &past_north_pole);
+        }
+
+        #[test]
+        fn try_new_non_finite(){
+            assert!(matches!(PolarVec::try_new(f64::NAN, 1.0, 1.0), Err(PolarError::NonFinite { .. })));
+            assert!(matches!(PolarVec::try_new(1.0, f64::NAN, 1.0), Err(PolarError::NonFinite { .. })));
+            assert!(matches!(PolarVec::try_new(1.0, 1.0, f64::INFINITY), Err(PolarError::NonFinite { .. })));
+        }
+
+        #[test]
+        fn try_new_out_of_range(){
+            assert_eq!(Err(PolarError::PhiOutOfRange { phi: 90.0, tolerance: DEFAULT_ANGLE_TOLERANCE }),
+                       PolarVec::try_new(1.0, 90.0, 1.0));
+            assert_eq!(Err(PolarError::ThetaOutOfRange { theta: -4.0, tolerance: DEFAULT_ANGLE_TOLERANCE }),
+                       PolarVec::try_new(1.0, 1.0, -4.0));
+            assert_eq!(Err(PolarError::ThetaOutOfRange { theta: 3.5, tolerance: 0.0 }),
+                       PolarVec::try_new_with_tolerance(1.0, 1.0, 3.5, 0.0));
+            assert_eq!("azimut angle 90 rad lies more than 0 rad outside of 0..2*pi",
+                       PolarVec::try_new_with_tolerance(1.0, 90.0, 1.0, 0.0).unwrap_err().to_string());
+        }
+
+        #[test]
+        fn from_degrees(){
+            assert_eq!(PolarVec::new(5.0, FRAC_PI_2, FRAC_PI_2), PolarVec::from_degrees(5.0, 90.0, 90.0));
+            assert_eq!(PolarVec::new(2.0, PI, FRAC_PI_2 / 2.0), PolarVec::from_degrees(2.0, -180.0, 45.0));
+            assert_eq!(PolarVec::new(2.0, 0.1, 1.0), PolarVec::from_degrees(2.0, 0.1_f64.to_degrees() + 360.0, 1.0_f64.to_degrees()));
+
+            // A negative polar angle leans over the north pole onto the opposite azimuth.
+            let a = PolarVec::from_degrees(1.0, 0.0, -10.0);
+            assert_eq!(PolarVec::from_degrees(1.0, 180.0, 10.0), a);
+            let theta = 10.0_f64.to_radians();
+            assert_vec_eq(&Vector::new(-theta.sin(), 0.0, theta.cos()), &a.to_vector());
+        }
+
+        #[test]
+        fn get_in_deg(){
+            let a = PolarVec::from_degrees(5.0, 270.0, 30.0);
+            assert!(equal_within_delta(270.0, a.get_phi_in_deg()));
+            assert!(equal_within_delta(30.0, a.get_theta_in_deg()));
+            assert_eq!((a.get_phi_in_deg(), a.get_theta_in_deg()), a.to_degrees());
+        }
+
+        #[test]
+        fn to_degrees(){
+            let a = PolarVec::new(10.0, PI, FRAC_PI_2);
+            assert_eq!((180.0, 90.0), a.to_degrees());
+        }
+    }
+
+    mod float_types {
+        use crate::physics::vectors::{PolarVec32, PolarVec64, Vec32, Vec64};
+
+        #[test]
+        fn arithmetic_in_both_types(){
+            let a = Vec64::new(3.0, 4.0, 12.0);
+            let b = Vec32::new(3.0, 4.0, 12.0);
+            assert_eq!(13.0, a.magnitude());
+            assert_eq!(13.0_f32, b.magnitude());
+            assert_eq!(Vec64::new(4.0, 6.0, 15.0), a.add(&Vec64::new(1.0, 2.0, 3.0)));
+            assert_eq!(Vec32::new(4.0, 6.0, 15.0), b.add(&Vec32::new(1.0, 2.0, 3.0)));
+            assert_eq!(Vec32::new(0.0, 0.0, 1.0), Vec32::new(1.0, 0.0, 0.0).cross(&Vec32::new(0.0, 1.0, 0.0)));
+            assert_eq!("[X: 3.0 m, Y: 4.0 m, Z: 12.0 m]", format!("{:.1}", b));
+        }
+
+        #[test]
+        fn polar_in_both_types(){
+            assert_eq!(PolarVec64::new(13.0, 0.0, 0.0), Vec64::new(0.0, 0.0, 13.0).to_polar_vector());
+            assert_eq!(PolarVec32::new(13.0, 0.0, 0.0), Vec32::new(0.0, 0.0, 13.0).to_polar_vector());
+
+            let a = Vec32::new(-20.0, 35.0, -8.0);
+            let round_trip = a.to_polar_vector().to_vector();
+            for axis in 0..3 {
+                assert!((a[axis] - round_trip[axis]).abs() < 1e-4);
+            }
+            assert!(PolarVec32::try_new(5.0, 90.0, 90.0).is_err());
+        }
+
+        #[test]
+        fn polar_round_trip_precision(){
+            // A contact almost straight above the sensor: cos(theta) lies so close to 1 that single
+            // values round it to 1 and lose the horizontal offset, while double values keep it.
+            let a = Vec64::new(0.001, 0.0, 1000.0).to_polar_vector().to_vector();
+            assert!((0.001 - a.get_x()).abs() < 1e-6);
+            assert!((1000.0 - a.get_z()).abs() < 1e-9);
+
+            let b = Vec32::new(0.001, 0.0, 1000.0).to_polar_vector().to_vector();
+            assert_eq!(0.0, b.get_x());
+            assert_eq!(1000.0, b.get_z());
         }
     }
-}
 
+    mod point_sets {
+        use crate::physics::vectors::{bounding_box, centroid, Vector};
+
+        fn scattered() -> Vec<Vector> {
+            vec![
+                Vector::new(12.0, -3.0, -40.0),
+                Vector::new(-8.0, 7.0, -35.0),
+                Vector::new(4.0, 21.0, -52.0),
+                Vector::new(0.0, -11.0, -41.0),
+                Vector::new(2.0, 1.0, -32.0),
+            ]
+        }
+
+        #[test]
+        fn centroid_of_points() {
+            assert_eq!(Some(Vector::new(2.0, 3.0, -40.0)), centroid(&scattered()));
+            assert_eq!(Some(Vector::new(1.0, 2.0, 3.0)), centroid(&[Vector::new(1.0, 2.0, 3.0)]));
+            assert_eq!(None, centroid(&[]));
+        }
+
+        #[test]
+        fn bounding_box_of_points() {
+            let (min, max) = bounding_box(&scattered()).unwrap();
+            assert_eq!(Vector::new(-8.0, -11.0, -52.0), min);
+            assert_eq!(Vector::new(12.0, 21.0, -32.0), max);
+            assert!(scattered().iter().all(|p| p.component_max(&min) == *p && p.component_min(&max) == *p));
+
+            let single = Vector::new(1.0, 2.0, 3.0);
+            assert_eq!(Some((single, single)), bounding_box(&[single]));
+            assert_eq!(None, bounding_box(&[]));
+        }
+    }
+
+    mod cloud_fingerprint {
+        use crate::physics::vectors::{cloud_fingerprint, Vector, FINGERPRINT_QUANTUM};
+
+        fn cloud() -> Vec<Vector> {
+            (0..50).map(|i| Vector::new(i as f64 * 1.7, -3.2 * i as f64, 0.25 * i as f64 - 10.0)).collect()
+        }
+
+        #[test]
+        fn stable_under_noise() {
+            let noisy: Vec<Vector> = cloud().iter().enumerate()
+                .map(|(i, point)| {
+                    let noise = if i % 2 == 0 { 0.01 } else { -0.01 } * FINGERPRINT_QUANTUM;
+                    point.add(&Vector::new(noise, -noise, noise))
+                })
+                .collect();
+            assert_ne!(cloud(), noisy);
+            assert_eq!(cloud_fingerprint(&cloud()), cloud_fingerprint(&noisy));
+            assert_eq!(cloud_fingerprint(&cloud()), cloud_fingerprint(&cloud()));
+        }
+
+        #[test]
+        fn changes_with_cloud() {
+            let fingerprint = cloud_fingerprint(&cloud());
+
+            let mut moved = cloud();
+            moved[10] = moved[10].add(&Vector::new(0.0, 0.0, 0.5));
+            assert_ne!(fingerprint, cloud_fingerprint(&moved));
+
+            let mut shorter = cloud();
+            shorter.pop();
+            assert_ne!(fingerprint, cloud_fingerprint(&shorter));
+
+            let mut reordered = cloud();
+            reordered.swap(0, 1);
+            assert_ne!(fingerprint, cloud_fingerprint(&reordered));
+
+            assert_ne!(cloud_fingerprint(&[]), cloud_fingerprint(&[Vector::get_world_origin()]));
+        }
+    }
+}
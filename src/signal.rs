@@ -0,0 +1,179 @@
+#![allow(dead_code)]
+//! This module contains signal processing functions working on sampled range lines and waveforms.
+
+use std::f64::consts::TAU;
+
+/// Runs a cell-averaging constant false alarm rate (CA-CFAR) detector over a range line of power
+/// values and returns for every cell whether it holds a detection.
+///
+/// For every cell the noise is estimated as the mean of up to training_cells cells on each side,
+/// skipping guard_cells cells directly next to the cell under test. A cell is flagged if its power
+/// exceeds the noise estimate times threshold_factor. Near the ends of the range line the training
+/// window is shrunk to the available cells; a cell without any training cells is never flagged.
+/// # Examples
+/// ```rust
+/// let line = [1.0, 1.0, 1.0, 1.0, 20.0, 1.0, 1.0, 1.0, 1.0];
+/// let detections = cfar(&line, 1, 2, 5.0);
+/// assert!(detections[4]);
+/// assert!(!detections[0]);
+/// ```
+pub fn cfar(power_line: &[f64], guard_cells: usize, training_cells: usize,
+            threshold_factor: f64) -> Vec<bool> {
+    let len = power_line.len();
+    (0..len).map(|i| {
+        let mut sum = 0.0;
+        let mut count = 0;
+
+        let lead_end = i.saturating_sub(guard_cells);
+        let lead_start = i.saturating_sub(guard_cells + training_cells);
+        for power in &power_line[lead_start..lead_end] {
+            sum += power;
+            count += 1;
+        }
+
+        let lag_start = (i + guard_cells + 1).min(len);
+        let lag_end = (i + guard_cells + training_cells + 1).min(len);
+        for power in &power_line[lag_start..lag_end] {
+            sum += power;
+            count += 1;
+        }
+
+        count > 0 && power_line[i] > threshold_factor * sum / count as f64
+    }).collect()
+}
+
+/// Returns the magnitude of the delay-Doppler ambiguity surface of a sampled waveform.
+///
+/// The outer vector holds one row per Doppler shift, spaced evenly from -max_doppler_hz to
+/// max_doppler_hz over doppler_steps steps (a single step only evaluates zero Doppler). Every row
+/// holds one value per delay from -(n - 1) to n - 1 samples, where n is the waveform length, so
+/// zero delay sits at index n - 1.
+///
+/// The cost grows with doppler_steps * n², so long waveforms should be decimated first.
+/// # Examples
+/// ```rust
+/// let surface = ambiguity(&[1.0, 1.0, 1.0], 1000.0, 100.0, 3);
+/// assert_eq!(3, surface.len());
+/// assert_eq!(5, surface[0].len());
+/// assert_eq!(3.0, surface[1][2]);
+/// ```
+pub fn ambiguity(waveform: &[f64], sample_rate_hz: f64, max_doppler_hz: f64,
+                 doppler_steps: usize) -> Vec<Vec<f64>> {
+    let len = waveform.len() as isize;
+    (0..doppler_steps).map(|step| {
+        let doppler_hz = if doppler_steps > 1 {
+            -max_doppler_hz + 2.0 * max_doppler_hz * step as f64 / (doppler_steps - 1) as f64
+        } else { 0.0 };
+        let omega = TAU * doppler_hz / sample_rate_hz;
+
+        (-(len - 1)..len).map(|delay| {
+            let mut re = 0.0;
+            let mut im = 0.0;
+            for n in 0.max(-delay)..len.min(len - delay) {
+                let product = waveform[n as usize] * waveform[(n + delay) as usize];
+                re += product * (omega * n as f64).cos();
+                im += product * (omega * n as f64).sin();
+            }
+            (re * re + im * im).sqrt()
+        }).collect()
+    }).collect()
+}
+
+/// Resamples a range line that was recorded assuming old_speed so that its samples sit on the same
+/// range grid under the corrected new_speed.
+///
+/// A sample at index i lies at the range `i * new_speed / old_speed` in the old grid units, so a
+/// faster corrected sound speed stretches the line and a slower one compresses it. The output
+/// covers the full stretched line and values between source samples are linearly interpolated.
+/// # Examples
+/// ```rust
+/// let line = rescale_range_line(&[0.0, 1.0, 2.0], 1500.0, 3000.0);
+/// assert_eq!(vec![0.0, 0.5, 1.0, 1.5, 2.0], line);
+/// ```
+pub fn rescale_range_line(line: &[f64], old_speed: f64, new_speed: f64) -> Vec<f64> {
+    if line.is_empty() {
+        return Vec::new();
+    }
+    let ratio = new_speed / old_speed;
+    let new_len = ((line.len() - 1) as f64 * ratio).floor() as usize + 1;
+    (0..new_len).map(|j| {
+        let source = j as f64 / ratio;
+        let lower = (source.floor() as usize).min(line.len() - 1);
+        let upper = (lower + 1).min(line.len() - 1);
+        let fraction = source - lower as f64;
+        line[lower] + (line[upper] - line[lower]) * fraction
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cfar_detects_spike(){
+        let mut line = vec![1.0, 1.2, 0.8, 1.1, 0.9, 1.0, 1.3, 0.7, 1.0, 1.1, 0.9, 1.0];
+        line[6] = 25.0;
+        let detections = cfar(&line, 1, 3, 4.0);
+
+        assert!(detections[6]);
+        assert_eq!(1, detections.iter().filter(|x| **x).count());
+    }
+
+    #[test]
+    fn cfar_noise_only(){
+        let line = vec![1.0, 1.2, 0.8, 1.1, 0.9, 1.0, 1.3, 0.7, 1.0, 1.1, 0.9, 1.0];
+        let detections = cfar(&line, 1, 3, 4.0);
+
+        assert_eq!(line.len(), detections.len());
+        assert!(detections.iter().all(|x| !*x));
+    }
+
+    #[test]
+    fn cfar_spike_at_edge(){
+        let line = vec![30.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+        let detections = cfar(&line, 1, 2, 4.0);
+
+        assert!(detections[0]);
+        assert!(detections[1..].iter().all(|x| !*x));
+    }
+
+    #[test]
+    fn ambiguity_peak_at_origin(){
+        let pulse = vec![1.0, 0.8, -0.6, 1.0, -1.0, 0.5, 0.9, -0.7];
+        let surface = ambiguity(&pulse, 8000.0, 1000.0, 11);
+        assert_eq!(11, surface.len());
+        assert_eq!(2 * pulse.len() - 1, surface[0].len());
+
+        let peak = surface[5][pulse.len() - 1];
+        let energy: f64 = pulse.iter().map(|x| x * x).sum();
+        assert!((peak - energy).abs() < 0.000001);
+        for row in &surface {
+            for value in row {
+                assert!(*value <= peak + 0.000001);
+            }
+        }
+        assert!(surface[0][pulse.len() - 1] < peak);
+        assert!(surface[5][pulse.len()] < peak);
+    }
+
+    #[test]
+    fn rescale_range_line_stretches(){
+        let mut line = vec![0.0; 21];
+        line[10] = 1.0;
+        let rescaled = rescale_range_line(&line, 1500.0, 1500.0 * 1.5);
+
+        assert_eq!(31, rescaled.len());
+        assert_eq!(15, rescaled.iter().enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1)).unwrap().0);
+        assert!((rescaled[15] - 1.0).abs() < 0.000001);
+    }
+
+    #[test]
+    fn rescale_range_line_interpolates(){
+        let line = vec![0.0, 2.0, 4.0, 6.0];
+        assert_eq!(line, rescale_range_line(&line, 1500.0, 1500.0));
+        assert_eq!(vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0], rescale_range_line(&line, 1500.0, 3000.0));
+        assert_eq!(vec![0.0, 4.0], rescale_range_line(&line, 3000.0, 1500.0));
+        assert!(rescale_range_line(&[], 1500.0, 1480.0).is_empty());
+    }
+}
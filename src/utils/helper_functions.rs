@@ -39,10 +39,21 @@
  */
 
 use crate::constants::F64_DELTA;
+#[cfg(test)]
+use crate::physics::vectors::Vector;
 
 /// This function compares two double values and returns true, when the difference between
 /// them is smaller than [F64_DELTA]
 /// [F64_DELTA]: GetLinkLocation
 pub fn equal_within_delta(a: f64, b: f64) -> bool{
-    return (a-b).abs() < F64_DELTA
+    (a-b).abs() < F64_DELTA
+}
+
+/// Asserts that every component of actual lies within [F64_DELTA] of the one of expected, for
+/// comparing computed vectors in tests.
+#[cfg(test)]
+pub fn assert_vec_eq(expected: &Vector, actual: &Vector) {
+    for axis in 0..3 {
+        assert!(equal_within_delta(expected[axis], actual[axis]), "{} != {}", expected, actual);
+    }
 }
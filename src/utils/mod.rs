@@ -38,4 +38,5 @@
  *
  */
 
+#![allow(dead_code)]
 pub mod helper_functions;
\ No newline at end of file